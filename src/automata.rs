@@ -1,11 +1,31 @@
-use crate::{GlobalPos, ChunkPos};
+mod asynchronous;
+mod block;
+mod generations;
+mod hashlife;
+mod life;
+mod period;
+mod rng;
+mod wireworld;
+
+pub use self::asynchronous::*;
+pub use self::block::*;
+pub use self::generations::*;
+pub use self::hashlife::*;
+pub use self::life::*;
+pub use self::period::*;
+pub use self::rng::*;
+pub use self::wireworld::*;
+use crate::{GlobalPos, ChunkPos, LocalPos};
 use crate::chunk::*;
 use crate::grid::*;
 use crate::vector::Vector2;
 
-use num_traits::Signed;
+use num_traits::{FromPrimitive, Signed};
+#[cfg(feature = "multi-thread")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::hash::BuildHasher;
 use std::mem::swap;
 
@@ -35,22 +55,7 @@ where T: Default, H: BuildHasher {
   type ChunkPos = ChunkPos;
 
   fn step_scratch(&mut self, scratch: &mut Self, automata: &mut impl Automata<Self>) {
-    scratch.clear();
-    for (&chunk_pos, chunk) in self.chunks() {
-      automata.expansion(chunk).apply_with_center(chunk_pos, |chunk_pos| {
-        if let Entry::Vacant(entry) = scratch.get_chunk_entry(chunk_pos) {
-          let mut chunk = Chunk::new();
-          for (local, value) in chunk.cells_mut() {
-            let pos = crate::grid::compose::<S>(chunk_pos, local);
-            *value = automata.simulate(pos, &self);
-          };
-
-          entry.insert(chunk);
-        };
-      });
-    };
-
-    swap(self, scratch);
+    step_chunks(self, scratch, automata, &Topology::Infinite, |_, _| Vec::new());
   }
 }
 
@@ -63,22 +68,305 @@ where H: BuildHasher {
   type ChunkPos = ChunkPos;
 
   fn step_scratch(&mut self, scratch: &mut Self, automata: &mut impl Automata<Self>) {
-    scratch.clear();
-    for (&chunk_pos, chunk) in self.chunks() {
-      automata.expansion(chunk).apply_with_center(chunk_pos, |chunk_pos| {
-        if let Entry::Vacant(entry) = scratch.get_chunk_entry(chunk_pos) {
-          let mut chunk = Chunk::new();
-          for (local, value) in chunk.cells_mut() {
-            let pos = crate::grid::compose::<S>(chunk_pos, local);
-            *value = automata.simulate(pos, &self);
-          };
-
-          entry.insert(chunk.into());
-        };
-      });
+    step_chunks(self, scratch, automata, &Topology::Infinite, |_, _| Vec::new());
+  }
+}
+
+/// Extends [`AutomataAdapter`] with stepping within a [`Topology`], along with statistics and change tracking between generations.
+///
+/// This is implemented for [`ExGrid`] and [`ExGridSparse`], and cannot be implemented outside of this crate.
+pub trait GridAutomataAdapter: AutomataAdapter + sealed::Sealed {
+  /// Steps the automata once within the given topology, using `scratch` to build the next state.
+  ///
+  /// While the step is simulated, the grid holds copies of the chunks that the topology maps each chunk
+  /// just outside of it to, so that [`Automata::simulate`] reads cells across an edge as the topology arranges them.
+  fn step_scratch_in(&mut self, scratch: &mut Self, automata: &mut impl Automata<Self>, topology: &Topology<Self::Chunk, Self::ChunkPos>)
+  where Self::Chunk: Clone;
+
+  fn step_in(&mut self, automata: &mut impl Automata<Self>, topology: &Topology<Self::Chunk, Self::ChunkPos>)
+  where Self: Default, Self::Chunk: Clone {
+    self.step_scratch_in(&mut Self::default(), automata, topology);
+  }
+
+  /// Steps the automata `n` times within the given topology, reusing `scratch` for every generation.
+  /// After each generation, `observer` is called with the grid and statistics describing that generation.
+  fn step_n_with(
+    &mut self,
+    scratch: &mut Self,
+    automata: &mut impl Automata<Self>,
+    topology: &Topology<Self::Chunk, Self::ChunkPos>,
+    n: usize,
+    mut observer: impl FnMut(&Self, GenerationStats)
+  ) where Self::Cell: PartialEq, Self::Chunk: Clone {
+    for _ in 0..n {
+      self.step_scratch_in(scratch, automata, topology);
+      // After stepping, the scratch grid holds the previous generation
+      observer(self, self.generation_stats(scratch));
+    };
+  }
+
+  /// Steps the automata `n` times, returning statistics describing each generation.
+  fn step_n(&mut self, automata: &mut impl Automata<Self>, n: usize) -> StepReport
+  where Self: Default, Self::Cell: PartialEq {
+    let mut scratch = Self::default();
+    let mut report = StepReport { generations: Vec::with_capacity(n) };
+    for _ in 0..n {
+      self.step_scratch(&mut scratch, automata);
+      report.generations.push(self.generation_stats(&scratch));
+    };
+
+    report
+  }
+
+  /// Compares this grid against the grid of the `previous` generation.
+  fn generation_stats(&self, previous: &Self) -> GenerationStats where Self::Cell: PartialEq;
+
+  /// Steps the automata once within the given topology, only simulating chunks which `active` reports as changed
+  /// during the previous step, or which lie within [`Automata::reach`] of such a chunk. Every other chunk is moved
+  /// forward unchanged, and `active` is updated with the chunks that changed during this step.
+  ///
+  /// This is only equivalent to [`GridAutomataAdapter::step_scratch_in`] for automata whose cells depend solely on
+  /// the state of the chunks within [`Automata::reach`], and not on the generation or any other state of the automata.
+  fn step_scratch_active(
+    &mut self,
+    scratch: &mut Self,
+    automata: &mut impl Automata<Self>,
+    topology: &Topology<Self::Chunk, Self::ChunkPos>,
+    active: &mut ActiveChunks<Self::ChunkPos>
+  ) where Self::Cell: PartialEq, Self::Chunk: Clone;
+
+  fn step_active(&mut self, automata: &mut impl Automata<Self>, active: &mut ActiveChunks<Self::ChunkPos>)
+  where Self: Default, Self::Cell: PartialEq;
+
+  /// Returns true if every cell in `chunk` is vacant, meaning the chunk may be dropped without changing the grid.
+  fn is_chunk_vacant(chunk: &Self::Chunk) -> bool where Self::Cell: PartialEq;
+}
+
+impl<A> GridAutomataAdapter for A
+where A: ChunkMap, A::Chunk: Default {
+  fn step_scratch_in(&mut self, scratch: &mut Self, automata: &mut impl Automata<Self>, topology: &Topology<A::Chunk>)
+  where A::Chunk: Clone {
+    let reach = automata.reach();
+    step_chunks(self, scratch, automata, topology, |grid, positions| insert_edge_chunks(grid, topology, positions, reach));
+  }
+
+  fn generation_stats(&self, previous: &Self) -> GenerationStats where A::Cell: PartialEq {
+    generation_stats(self, previous)
+  }
+
+  fn step_scratch_active(&mut self, scratch: &mut Self, automata: &mut impl Automata<Self>, topology: &Topology<A::Chunk>, active: &mut ActiveChunks)
+  where A::Cell: PartialEq, A::Chunk: Clone {
+    let reach = automata.reach();
+    step_chunks_active(self, scratch, automata, topology, active, |grid, positions| insert_edge_chunks(grid, topology, positions, reach));
+  }
+
+  fn step_active(&mut self, automata: &mut impl Automata<Self>, active: &mut ActiveChunks)
+  where A: Default, A::Cell: PartialEq {
+    step_chunks_active(self, &mut A::default(), automata, &Topology::Infinite, active, |_, _| Vec::new());
+  }
+
+  fn is_chunk_vacant(chunk: &A::Chunk) -> bool where A::Cell: PartialEq {
+    A::count_occupied(chunk) == 0
+  }
+}
+
+mod sealed {
+  /// Prevents [`GridAutomataAdapter`](super::GridAutomataAdapter) from being implemented outside of this crate.
+  pub trait Sealed {}
+
+  impl<A: super::ChunkMap> Sealed for A {}
+}
+
+/// Describes a generic structure upon which a cellular automata may operate, whose chunks may be simulated in parallel.
+#[cfg(feature = "multi-thread")]
+pub trait ParAutomataAdapter: AutomataAdapter + Sync {
+  /// Performs the same step as [`GridAutomataAdapter::step_scratch_in`], simulating each chunk in parallel.
+  /// Expansion is evaluated serially on `automata`, while each thread simulates cells using its own clone of `automata`,
+  /// so any changes made to the state of the automata while simulating cells are discarded.
+  fn par_step_scratch_in<U>(&mut self, scratch: &mut Self, automata: &mut U, topology: &Topology<Self::Chunk, Self::ChunkPos>)
+  where Self::Chunk: Clone, U: Automata<Self> + Clone + Send;
+
+  fn par_step_scratch<U>(&mut self, scratch: &mut Self, automata: &mut U)
+  where U: Automata<Self> + Clone + Send;
+
+  fn par_step<U>(&mut self, automata: &mut U)
+  where Self: Default, U: Automata<Self> + Clone + Send {
+    self.par_step_scratch(&mut Self::default(), automata);
+  }
+
+  fn par_step_in<U>(&mut self, automata: &mut U, topology: &Topology<Self::Chunk, Self::ChunkPos>)
+  where Self: Default, Self::Chunk: Clone, U: Automata<Self> + Clone + Send {
+    self.par_step_scratch_in(&mut Self::default(), automata, topology);
+  }
+}
+
+#[cfg(feature = "multi-thread")]
+impl<T, const S: usize, H> ParAutomataAdapter for ExGrid<T, S, H>
+where T: Default + Send + Sync, H: BuildHasher + Sync {
+  fn par_step_scratch_in<U>(&mut self, scratch: &mut Self, automata: &mut U, topology: &Topology<Chunk<T, S>>)
+  where Chunk<T, S>: Clone, U: Automata<Self> + Clone + Send {
+    let reach = automata.reach();
+    par_step_chunks(self, scratch, automata, topology, |grid, positions| insert_edge_chunks(grid, topology, positions, reach));
+  }
+
+  fn par_step_scratch<U>(&mut self, scratch: &mut Self, automata: &mut U)
+  where U: Automata<Self> + Clone + Send {
+    par_step_chunks(self, scratch, automata, &Topology::Infinite, |_, _| Vec::new());
+  }
+}
+
+#[cfg(feature = "multi-thread")]
+impl<T, const S: usize, H> ParAutomataAdapter for ExGridSparse<T, S, H>
+where T: Send + Sync, H: BuildHasher + Sync {
+  fn par_step_scratch_in<U>(&mut self, scratch: &mut Self, automata: &mut U, topology: &Topology<ChunkSparse<T, S>>)
+  where ChunkSparse<T, S>: Clone, U: Automata<Self> + Clone + Send {
+    let reach = automata.reach();
+    par_step_chunks(self, scratch, automata, topology, |grid, positions| insert_edge_chunks(grid, topology, positions, reach));
+  }
+
+  fn par_step_scratch<U>(&mut self, scratch: &mut Self, automata: &mut U)
+  where U: Automata<Self> + Clone + Send {
+    par_step_chunks(self, scratch, automata, &Topology::Infinite, |_, _| Vec::new());
+  }
+}
+
+/// The chunk storage of the grids, allowing them to share their stepping logic.
+trait ChunkMap: AutomataAdapter<CellPos = GlobalPos, ChunkPos = ChunkPos> {
+  const SIZE: usize;
+
+  fn chunk_iter(&self) -> impl Iterator<Item = (&ChunkPos, &Self::Chunk)>;
+
+  fn chunk(&self, pos: ChunkPos) -> Option<&Self::Chunk>;
+
+  fn chunk_entry(&mut self, pos: ChunkPos) -> Entry<'_, ChunkPos, Self::Chunk>;
+
+  fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Self::Chunk> {
+    match self.chunk_entry(pos) {
+      Entry::Occupied(entry) => Some(entry.remove()),
+      Entry::Vacant(_) => None
+    }
+  }
+
+  fn retain_chunks(&mut self, f: impl FnMut(&ChunkPos, &Self::Chunk) -> bool);
+
+  fn clear_chunks(&mut self);
+
+  fn count_occupied(chunk: &Self::Chunk) -> usize where Self::Cell: PartialEq;
+
+  fn count_changed(chunk: &Self::Chunk, previous: &Self::Chunk) -> usize where Self::Cell: PartialEq;
+
+  /// Calls `f` with the position and a mutable reference to the value of every cell in `chunk`, which is located at `pos`.
+  fn for_each_cell_mut(pos: ChunkPos, chunk: &mut Self::Chunk, f: impl FnMut(GlobalPos, &mut Self::Cell));
+
+  /// Takes the value of a cell out of the grid, leaving it vacant.
+  fn take_cell(&mut self, pos: GlobalPos) -> Self::Cell;
+
+  /// Sets the value of a cell, only creating a chunk if the value is not vacant.
+  fn put_cell(&mut self, pos: GlobalPos, cell: Self::Cell) where Self::Cell: PartialEq;
+}
+
+impl<T, const S: usize, H> ChunkMap for ExGrid<T, S, H>
+where T: Default, H: BuildHasher {
+  const SIZE: usize = S;
+
+  fn chunk_iter(&self) -> impl Iterator<Item = (&ChunkPos, &Chunk<T, S>)> {
+    self.chunks()
+  }
+
+  fn chunk(&self, pos: ChunkPos) -> Option<&Chunk<T, S>> {
+    self.get_chunk(pos)
+  }
+
+  fn chunk_entry(&mut self, pos: ChunkPos) -> Entry<'_, ChunkPos, Chunk<T, S>> {
+    self.get_chunk_entry(pos)
+  }
+
+  fn retain_chunks(&mut self, mut f: impl FnMut(&ChunkPos, &Self::Chunk) -> bool) {
+    self.retain(|pos, chunk| f(pos, chunk));
+  }
+
+  fn clear_chunks(&mut self) {
+    self.clear();
+  }
+
+  fn count_occupied(chunk: &Chunk<T, S>) -> usize where T: PartialEq {
+    let default = T::default();
+    chunk.iter().filter(|&value| *value != default).count()
+  }
+
+  fn count_changed(chunk: &Chunk<T, S>, previous: &Chunk<T, S>) -> usize where T: PartialEq {
+    chunk.iter().zip(previous.iter()).filter(|(a, b)| a != b).count()
+  }
+
+  fn for_each_cell_mut(pos: ChunkPos, chunk: &mut Chunk<T, S>, mut f: impl FnMut(GlobalPos, &mut T)) {
+    for (local, value) in chunk.cells_mut() {
+      f(crate::grid::compose::<S>(pos, local), value);
     };
+  }
+
+  fn take_cell(&mut self, pos: GlobalPos) -> T {
+    self.get_mut(pos).map(std::mem::take).unwrap_or_default()
+  }
 
-    swap(self, scratch);
+  fn put_cell(&mut self, pos: GlobalPos, cell: T) where T: PartialEq {
+    match self.get_mut(pos) {
+      Some(value) => *value = cell,
+      None if cell != T::default() => *self.get_mut_default(pos) = cell,
+      None => ()
+    };
+  }
+}
+
+impl<T, const S: usize, H> ChunkMap for ExGridSparse<T, S, H>
+where H: BuildHasher {
+  const SIZE: usize = S;
+
+  fn chunk_iter(&self) -> impl Iterator<Item = (&ChunkPos, &ChunkSparse<T, S>)> {
+    self.chunks()
+  }
+
+  fn chunk(&self, pos: ChunkPos) -> Option<&ChunkSparse<T, S>> {
+    self.get_chunk(pos)
+  }
+
+  fn chunk_entry(&mut self, pos: ChunkPos) -> Entry<'_, ChunkPos, ChunkSparse<T, S>> {
+    self.get_chunk_entry(pos)
+  }
+
+  fn retain_chunks(&mut self, mut f: impl FnMut(&ChunkPos, &Self::Chunk) -> bool) {
+    self.retain(|pos, chunk| f(pos, chunk));
+  }
+
+  fn clear_chunks(&mut self) {
+    self.clear();
+  }
+
+  fn count_occupied(chunk: &ChunkSparse<T, S>) -> usize where Option<T>: PartialEq {
+    chunk.as_chunk().iter().filter(|value| value.is_some()).count()
+  }
+
+  fn count_changed(chunk: &ChunkSparse<T, S>, previous: &ChunkSparse<T, S>) -> usize where Option<T>: PartialEq {
+    chunk.as_chunk().iter().zip(previous.as_chunk().iter()).filter(|(a, b)| a != b).count()
+  }
+
+  fn for_each_cell_mut(pos: ChunkPos, chunk: &mut ChunkSparse<T, S>, mut f: impl FnMut(GlobalPos, &mut Option<T>)) {
+    for (local, value) in chunk.as_chunk_mut().cells_mut() {
+      f(crate::grid::compose::<S>(pos, local), value);
+    };
+  }
+
+  fn take_cell(&mut self, pos: GlobalPos) -> Option<T> {
+    let (chunk_pos, local) = crate::grid::decompose::<S>(pos);
+    self.get_chunk_mut(chunk_pos).and_then(|chunk| chunk[local].take())
+  }
+
+  fn put_cell(&mut self, pos: GlobalPos, cell: Option<T>) where Option<T>: PartialEq {
+    let (chunk_pos, local) = crate::grid::decompose::<S>(pos);
+    match self.get_chunk_mut(chunk_pos) {
+      Some(chunk) => chunk[local] = cell,
+      None if cell.is_some() => self.get_chunk_default(chunk_pos)[local] = cell,
+      None => ()
+    };
   }
 }
 
@@ -90,8 +378,288 @@ pub trait Automata<A: AutomataAdapter> {
   /// prevent the automata from becoming trapped in a limited number of chunks.
   fn expansion(&mut self, chunk: &A::Chunk) -> Self::Expansion;
 
+  /// The number of chunks around a chunk within which changes may affect its cells when it is simulated.
+  /// [`GridAutomataAdapter::step_scratch_active`] simulates every chunk within this many chunks of a chunk that changed.
+  ///
+  /// By default, this is 1, which suits automata whose cells only read cells at most `S` cells away.
+  /// Automata which read further should return the [`chunk_radius`] of their neighbourhood.
+  fn reach(&self) -> u32 {
+    1
+  }
+
+  /// Rule that determines when a newly simulated chunk may be dropped. Chunks for which this returns true
+  /// are only dropped if none of their neighbouring chunks are kept, since [`Automata::expansion`] may not recreate them in time.
+  ///
+  /// By default, this returns false so that chunks are never dropped. Automata may opt in to dropping chunks
+  /// whose cells are all vacant by returning [`GridAutomataAdapter::is_chunk_vacant`].
+  fn contraction(&mut self, chunk: &A::Chunk) -> bool {
+    let _ = chunk;
+    false
+  }
+
   /// Rule that determines the value of a given cell based on the current state of the automata.
+  ///
+  /// When stepped within a [`Topology`], cells up to [`Automata::reach`] chunks beyond its edges
+  /// read from `grid` as the cells the topology maps them to.
   fn simulate(&mut self, pos: A::CellPos, grid: &A) -> A::Cell;
+
+  /// Rule that determines the values of every cell in a chunk at once, writing them into `chunk`,
+  /// which starts out empty. `neighbourhood` holds the chunk at the same position in `grid`
+  /// along with its neighbours, so that cells can be read without looking up their chunks.
+  ///
+  /// Returns false if the chunk was not simulated, in which case [`Automata::simulate`] is called for every cell in it instead.
+  /// By default, this returns false.
+  fn simulate_chunk(&mut self, pos: A::ChunkPos, chunk: &mut A::Chunk, neighbourhood: &ChunkNeighbourhood<'_, A::Chunk>, grid: &A) -> bool {
+    let _ = (pos, chunk, neighbourhood, grid);
+    false
+  }
+
+  /// Called once every chunk of a step has been simulated.
+  /// Automata whose state changes from one generation to the next, such as a [`CellRng`], should advance it here.
+  fn finish_step(&mut self) {}
+}
+
+/// A view of a chunk along with its eight neighbouring chunks, any of which may not exist.
+/// Chunks are addressed by their offset from the center chunk, from `[-1, -1]` to `[1, 1]`.
+#[derive(Debug)]
+pub struct ChunkNeighbourhood<'a, C> {
+  chunks: [[Option<&'a C>; 3]; 3]
+}
+
+impl<'a, C> ChunkNeighbourhood<'a, C> {
+  /// Creates a view by calling `f` with the offset of each chunk.
+  pub fn from_fn(mut f: impl FnMut([i32; 2]) -> Option<&'a C>) -> Self {
+    let chunks = std::array::from_fn(|y| std::array::from_fn(|x| f([x as i32 - 1, y as i32 - 1])));
+    ChunkNeighbourhood { chunks }
+  }
+
+  /// Gets the chunk at the given offset from the center chunk.
+  ///
+  /// # Panics
+  /// Panics if either component of `offset` is not between -1 and 1.
+  pub fn get(&self, offset: [i32; 2]) -> Option<&'a C> {
+    let [x, y] = offset.map(|v| usize::try_from(v + 1).ok().filter(|&v| v < 3).expect("offset out of bounds"));
+    self.chunks[y][x]
+  }
+
+  pub fn center(&self) -> Option<&'a C> {
+    self.chunks[1][1]
+  }
+
+  /// Returns true if none of the chunks in this view exist.
+  pub fn is_empty(&self) -> bool {
+    self.chunks.iter().flatten().all(Option::is_none)
+  }
+}
+
+impl<'a, T, const S: usize> ChunkNeighbourhood<'a, Chunk<T, S>> {
+  /// Gets a reference to the value of a cell if the chunk it is located in exists.
+  /// `pos` is relative to the first cell of the center chunk, and must lie within this view.
+  pub fn get_cell(&self, pos: [i64; 2]) -> Option<&'a T> {
+    let (offset, local) = crate::grid::decompose::<S>(pos);
+    self.get(offset).map(|chunk| &chunk[local])
+  }
+}
+
+impl<'a, T, const S: usize> ChunkNeighbourhood<'a, ChunkSparse<T, S>> {
+  /// Gets a reference to the value of a cell if it is occupied.
+  /// `pos` is relative to the first cell of the center chunk, and must lie within this view.
+  pub fn get_cell(&self, pos: [i64; 2]) -> Option<&'a T> {
+    let (offset, local) = crate::grid::decompose::<S>(pos);
+    self.get(offset).and_then(|chunk| chunk[local].as_ref())
+  }
+}
+
+impl<'a, C> Clone for ChunkNeighbourhood<'a, C> {
+  #[inline]
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'a, C> Copy for ChunkNeighbourhood<'a, C> {}
+
+/// The shape of the world that an automata is simulated in, in terms of chunks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Topology<C, P = ChunkPos> {
+  /// An infinite plane, where chunks may be created anywhere.
+  #[default]
+  Infinite,
+  /// A rectangle of chunks between `min` and `max` (inclusive).
+  /// No chunks are simulated outside of the rectangle, and any neighbouring chunks
+  /// outside of the rectangle are read as `boundary`.
+  Bounded { min: P, max: P, boundary: C },
+  /// A rectangle of chunks between `min` and `max` (inclusive), where each edge wraps around to the opposite edge.
+  /// Prefer [`Topology::torus`], which checks that `min` is no greater than `max`.
+  Torus { min: P, max: P }
+}
+
+impl<C> Topology<C> {
+  /// Creates a torus topology wrapping around the rectangle of chunks between `min` and `max` (inclusive).
+  ///
+  /// # Panics
+  /// Panics if `min` is greater than `max` on either axis.
+  pub fn torus(min: ChunkPos, max: ChunkPos) -> Self {
+    assert!(min[0] <= max[0] && min[1] <= max[1], "torus minimum {min:?} must not exceed its maximum {max:?}");
+    Topology::Torus { min, max }
+  }
+
+  /// Maps a chunk position onto the position of the chunk it refers to within this topology,
+  /// or `None` if it lies outside of a bounded topology.
+  ///
+  /// # Panics
+  /// Panics if this is a torus whose `min` is greater than its `max` on either axis.
+  pub fn resolve(&self, pos: ChunkPos) -> Option<ChunkPos> {
+    match *self {
+      Topology::Infinite => Some(pos),
+      Topology::Bounded { min, max, .. } => {
+        let contains = (0..2).all(|i| (min[i]..=max[i]).contains(&pos[i]));
+        contains.then_some(pos)
+      },
+      Topology::Torus { min, max } => Some(std::array::from_fn(|i| {
+        assert!(min[i] <= max[i], "torus minimum {min:?} must not exceed its maximum {max:?}");
+        let extent = i64::from(max[i]) - i64::from(min[i]) + 1;
+        // The result lies within `min..=max`, so it always fits in an `i32`
+        (i64::from(min[i]) + (i64::from(pos[i]) - i64::from(min[i])).rem_euclid(extent)) as i32
+      }))
+    }
+  }
+
+  /// Returns the chunk read in place of chunks outside of a bounded topology.
+  pub fn boundary(&self) -> Option<&C> {
+    match self {
+      Topology::Bounded { boundary, .. } => Some(boundary),
+      _ => None
+    }
+  }
+
+  /// Gets a view of the chunk at `pos` along with its eight neighbouring chunks as they are arranged within this topology,
+  /// using `get` to look up chunks.
+  pub fn neighbourhood<'a>(&'a self, pos: ChunkPos, mut get: impl FnMut(ChunkPos) -> Option<&'a C>) -> ChunkNeighbourhood<'a, C> {
+    ChunkNeighbourhood::from_fn(|[x, y]| match self.resolve([pos[0] + x, pos[1] + y]) {
+      Some(pos) => get(pos),
+      None => self.boundary()
+    })
+  }
+}
+
+impl<T: Clone, const S: usize> Topology<Chunk<T, S>> {
+  /// Creates a bounded topology in which every cell outside of the rectangle is read as `boundary`.
+  pub fn bounded(min: ChunkPos, max: ChunkPos, boundary: T) -> Self {
+    Topology::Bounded { min, max, boundary: Chunk::init(|_| boundary.clone()) }
+  }
+}
+
+impl<T: Clone, const S: usize> Topology<ChunkSparse<T, S>> {
+  /// Creates a bounded topology in which every cell outside of the rectangle is read as `boundary`.
+  pub fn bounded(min: ChunkPos, max: ChunkPos, boundary: Option<T>) -> Self {
+    Topology::Bounded { min, max, boundary: ChunkSparse::init(|_| boundary.clone()) }
+  }
+}
+
+/// Statistics describing a single generation of an automata, compared against the generation before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GenerationStats {
+  /// The number of cells which are not vacant.
+  pub population: usize,
+  /// The number of cells whose value changed.
+  pub changed: usize,
+  /// The number of chunks which did not exist in the previous generation.
+  pub chunks_created: usize,
+  /// The number of chunks from the previous generation which no longer exist.
+  pub chunks_dropped: usize
+}
+
+/// Statistics describing every generation stepped by [`GridAutomataAdapter::step_n`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct StepReport {
+  pub generations: Vec<GenerationStats>
+}
+
+impl StepReport {
+  /// The population of each generation.
+  pub fn populations(&self) -> impl Iterator<Item = usize> + '_ {
+    self.generations.iter().map(|stats| stats.population)
+  }
+
+  /// The population of the last generation, or `None` if no generations were stepped.
+  pub fn final_population(&self) -> Option<usize> {
+    self.generations.last().map(|stats| stats.population)
+  }
+
+  /// The total number of cell changes across every generation.
+  pub fn total_changed(&self) -> usize {
+    self.generations.iter().map(|stats| stats.changed).sum()
+  }
+
+  /// The total number of chunks created across every generation.
+  pub fn total_chunks_created(&self) -> usize {
+    self.generations.iter().map(|stats| stats.chunks_created).sum()
+  }
+
+  /// The total number of chunks dropped across every generation.
+  pub fn total_chunks_dropped(&self) -> usize {
+    self.generations.iter().map(|stats| stats.chunks_dropped).sum()
+  }
+}
+
+/// Tracks the chunks which changed during the last step of an automata,
+/// so that [`GridAutomataAdapter::step_scratch_active`] can skip chunks whose surroundings have not changed.
+///
+/// A new tracker treats every chunk as changed. Any changes made to the grid outside of stepping
+/// must be reported with [`ActiveChunks::mark_changed`] or [`ActiveChunks::reset`], otherwise they may be ignored.
+#[derive(Debug, Clone)]
+pub struct ActiveChunks<P = ChunkPos> {
+  changed: Option<HashSet<P>>
+}
+
+impl ActiveChunks {
+  pub fn new() -> Self {
+    ActiveChunks { changed: None }
+  }
+
+  /// Treats every chunk as changed, so that the next step simulates the whole grid.
+  pub fn reset(&mut self) {
+    self.changed = None;
+  }
+
+  /// Marks the chunk at `pos` as changed.
+  pub fn mark_changed(&mut self, pos: ChunkPos) {
+    if let Some(changed) = &mut self.changed {
+      changed.insert(pos);
+    };
+  }
+
+  /// Marks the chunk containing the cell at `pos` as changed, for grids with chunks of size `S`.
+  pub fn mark_cell_changed<const S: usize>(&mut self, pos: GlobalPos) {
+    self.mark_changed(crate::grid::decompose::<S>(pos).0);
+  }
+
+  /// Returns true if the chunk at `pos` changed during the last step.
+  pub fn is_changed(&self, pos: ChunkPos) -> bool {
+    self.changed.as_ref().is_none_or(|changed| changed.contains(&pos))
+  }
+
+  /// The number of chunks which changed during the last step, or `None` if every chunk is treated as changed.
+  pub fn changed_count(&self) -> Option<usize> {
+    self.changed.as_ref().map(HashSet::len)
+  }
+
+  /// Returns true if the chunk at `pos` or any chunk within `reach` chunks of it within the topology changed during the last step.
+  fn is_near_change<C>(&self, pos: ChunkPos, topology: &Topology<C>, reach: u32) -> bool {
+    let Some(changed) = &self.changed else { return true };
+    let reach = i64::from(reach);
+    (-reach..=reach).any(|y| (-reach..=reach).any(|x| {
+      let [Ok(x), Ok(y)] = [i64::from(pos[0]) + x, i64::from(pos[1]) + y].map(i32::try_from) else { return false };
+      topology.resolve([x, y]).is_some_and(|pos| changed.contains(&pos))
+    }))
+  }
+}
+
+impl Default for ActiveChunks {
+  fn default() -> Self {
+    ActiveChunks::new()
+  }
 }
 
 pub trait Expansion<P: Copy>: Sized {
@@ -103,7 +671,7 @@ pub trait Expansion<P: Copy>: Sized {
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Expansion4 {
   /// Vector `[ 0, -1]`
   pub n: bool,
@@ -137,18 +705,8 @@ impl<T> Expansion<[T; 2]> for Expansion4 where T: Signed + Copy {
   }
 }
 
-impl Default for Expansion4 {
-  fn default() -> Self {
-    Expansion4 {
-      n: false,
-      s: false,
-      e: false,
-      w: false
-    }
-  }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Expansion8 {
   /// Vector `[ 0, -1]`
   pub nn: bool,
@@ -213,31 +771,430 @@ impl From<Expansion4> for Expansion8 {
   }
 }
 
-impl Default for Expansion8 {
-  fn default() -> Self {
+
+/// Expands into every chunk within a square of radius `r` chunks around the center chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ExpansionRadius {
+  pub r: u32
+}
+
+impl ExpansionRadius {
+  /// Creates an expansion reaching every chunk which could contain a cell
+  /// within `cell_radius` cells of a cell in the center chunk, for chunks of size `S`.
+  pub const fn for_cell_radius<const S: usize>(cell_radius: usize) -> Self {
+    ExpansionRadius { r: chunk_radius::<S>(cell_radius) }
+  }
+}
+
+impl<T> Expansion<[T; 2]> for ExpansionRadius where T: Signed + FromPrimitive + Copy {
+  fn apply(self, pos: [T; 2], mut f: impl FnMut([T; 2])) {
+    let r = i64::from(self.r);
+    let pos = Vector2::from_array(pos);
+    for y in -r..=r {
+      for x in -r..=r {
+        if x != 0 || y != 0 {
+          let rel = Vector2::new(x, y).map(|v| T::from_i64(v).expect("expansion radius should fit in a chunk position"));
+          f(Vector2::into_array(pos + rel));
+        };
+      };
+    };
+  }
+}
+
+/// Expands into the chunks at each of a set of offsets from the center chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ExpansionSet<T = i32> {
+  pub offsets: Vec<[T; 2]>
+}
+
+impl<T> Expansion<[T; 2]> for ExpansionSet<T> where T: Signed + Copy {
+  fn apply(self, pos: [T; 2], mut f: impl FnMut([T; 2])) {
+    let pos = Vector2::from_array(pos);
+    for offset in self.offsets {
+      f(Vector2::into_array(pos + Vector2::from_array(offset)));
+    };
+  }
+}
+
+impl<T> FromIterator<[T; 2]> for ExpansionSet<T> {
+  fn from_iter<I: IntoIterator<Item = [T; 2]>>(iter: I) -> Self {
+    ExpansionSet { offsets: iter.into_iter().collect() }
+  }
+}
+
+/// Returns the number of chunks of size `S` that a neighbourhood of `cell_radius` cells can reach across.
+pub const fn chunk_radius<const S: usize>(cell_radius: usize) -> u32 {
+  cell_radius.div_ceil(S) as u32
+}
+
+impl<T, const S: usize> Chunk<T, S> {
+  /// Marks each edge of this chunk on which any cell passes the provided predicate.
+  pub fn edges_expansion(&self, mut predicate: impl FnMut(&T) -> bool) -> Expansion4 {
+    let s = S - 1;
+    Expansion4 {
+      n: self.horizontal_slice_iter(0).any(&mut predicate),
+      s: self.horizontal_slice_iter(s).any(&mut predicate),
+      w: self.vertical_slice_iter(0).any(&mut predicate),
+      e: self.vertical_slice_iter(s).any(&mut predicate)
+    }
+  }
+
+  /// Marks each edge of this chunk on which any cell passes the provided predicate,
+  /// and each corner of this chunk whose corner cell passes the provided predicate.
+  pub fn edges_corners_expansion(&self, mut predicate: impl FnMut(&T) -> bool) -> Expansion8 {
+    let s = S - 1;
+    let Expansion4 { n, s: ss, e, w } = self.edges_expansion(&mut predicate);
     Expansion8 {
-      nn: false,
-      ne: false,
-      ee: false,
-      se: false,
-      ss: false,
-      sw: false,
-      ww: false,
-      nw: false
+      nn: n,
+      ne: predicate(&self[[s, 0]]),
+      ee: e,
+      se: predicate(&self[[s, s]]),
+      ss,
+      sw: predicate(&self[[0, s]]),
+      ww: w,
+      nw: predicate(&self[[0, 0]])
     }
   }
+
+  /// Finds every neighbouring chunk containing a cell within `cell_radius` cells (in both axes)
+  /// of any cell in this chunk that passes the provided predicate.
+  /// This may overestimate, as only the bounding box of the passing cells is considered.
+  pub fn radius_expansion(&self, cell_radius: usize, mut predicate: impl FnMut(&T) -> bool) -> ExpansionSet {
+    let bounds = self.cells().filter(|(_, cell)| predicate(cell)).fold(None, |bounds: Option<(LocalPos, LocalPos)>, ([x, y], _)| match bounds {
+      Some(([min_x, min_y], [max_x, max_y])) => Some(([x.min(min_x), y.min(min_y)], [x.max(max_x), y.max(max_y)])),
+      None => Some(([x, y], [x, y]))
+    });
+
+    radius_expansion::<S>(bounds, cell_radius)
+  }
 }
 
 impl<T, const S: usize> ChunkSparse<T, S> {
-  /// This function checks the edges of a given chunk, if any cells on a given edge
-  /// fails to pass the provided predicate or it is empty, that edge is marked.
-  pub fn edges_not_empty_expansion(&self) -> Expansion4 {
+  /// Marks each edge of this chunk on which any occupied cell passes the provided predicate.
+  pub fn edges_expansion(&self, mut predicate: impl FnMut(&T) -> bool) -> Expansion4 {
     let s = S - 1;
+    let mut predicate = |cell: &Option<T>| cell.as_ref().is_some_and(&mut predicate);
     Expansion4 {
-      n: self.horizontal_slice_iter(0).any(Option::is_some),
-      s: self.horizontal_slice_iter(s).any(Option::is_some),
-      w: self.vertical_slice_iter(0).any(Option::is_some),
-      e: self.vertical_slice_iter(s).any(Option::is_some),
+      n: self.horizontal_slice_iter(0).any(&mut predicate),
+      s: self.horizontal_slice_iter(s).any(&mut predicate),
+      w: self.vertical_slice_iter(0).any(&mut predicate),
+      e: self.vertical_slice_iter(s).any(&mut predicate)
     }
   }
+
+  /// Marks each edge of this chunk on which any occupied cell passes the provided predicate,
+  /// and each corner of this chunk whose corner cell is occupied and passes the provided predicate.
+  pub fn edges_corners_expansion(&self, mut predicate: impl FnMut(&T) -> bool) -> Expansion8 {
+    self.as_chunk().edges_corners_expansion(|cell| cell.as_ref().is_some_and(&mut predicate))
+  }
+
+  /// Marks each edge of this chunk on which any cell is occupied.
+  pub fn edges_not_empty_expansion(&self) -> Expansion4 {
+    self.edges_expansion(|_| true)
+  }
+
+  /// Finds every neighbouring chunk containing a cell within `cell_radius` cells (in both axes)
+  /// of any occupied cell in this chunk that passes the provided predicate.
+  /// This may overestimate, as only the bounding box of the passing cells is considered.
+  pub fn radius_expansion(&self, cell_radius: usize, mut predicate: impl FnMut(&T) -> bool) -> ExpansionSet {
+    self.as_chunk().radius_expansion(cell_radius, |cell| cell.as_ref().is_some_and(&mut predicate))
+  }
+}
+
+
+
+/// Chunks which were replaced in a grid while a step was simulated, along with the chunk each position held beforehand.
+type ReplacedChunks<C> = Vec<(ChunkPos, Option<C>)>;
+
+/// Steps a grid once, simulating every chunk that the automata expands into within the topology.
+/// `insert_edges` is called with the chunks about to be simulated, and returns the chunks it replaced in the grid.
+fn step_chunks<A>(
+  grid: &mut A,
+  scratch: &mut A,
+  automata: &mut impl Automata<A>,
+  topology: &Topology<A::Chunk>,
+  insert_edges: impl FnOnce(&mut A, &[ChunkPos]) -> ReplacedChunks<A::Chunk>
+) where A: ChunkMap, A::Chunk: Default {
+  let chunk_positions = expanded_chunks(grid, automata, topology);
+  let replaced = insert_edges(grid, &chunk_positions);
+  scratch.clear_chunks();
+  for chunk_pos in chunk_positions {
+    let chunk = simulate_chunk(automata, chunk_pos, grid, topology);
+    scratch.chunk_entry(chunk_pos).or_insert(chunk);
+  };
+
+  restore_chunks(grid, replaced);
+  contract_chunks(scratch, automata, topology);
+  swap(grid, scratch);
+  automata.finish_step();
+}
+
+/// Steps a grid once, simulating only the chunks near a chunk which changed during the last step.
+fn step_chunks_active<A>(
+  grid: &mut A,
+  scratch: &mut A,
+  automata: &mut impl Automata<A>,
+  topology: &Topology<A::Chunk>,
+  active: &mut ActiveChunks,
+  insert_edges: impl FnOnce(&mut A, &[ChunkPos]) -> ReplacedChunks<A::Chunk>
+) where A: ChunkMap, A::Chunk: Default, A::Cell: PartialEq {
+  let reach = automata.reach();
+  let mut skipped = HashSet::new();
+  let mut chunk_positions = Vec::new();
+  for chunk_pos in expanded_chunks(grid, automata, topology) {
+    // Chunks which did not exist are always simulated, since they may be newly reachable
+    if grid.chunk(chunk_pos).is_some() && !active.is_near_change(chunk_pos, topology, reach) {
+      skipped.insert(chunk_pos);
+    } else {
+      chunk_positions.push(chunk_pos);
+    };
+  };
+
+  let replaced = insert_edges(grid, &chunk_positions);
+  scratch.clear_chunks();
+  for chunk_pos in chunk_positions {
+    let chunk = simulate_chunk(automata, chunk_pos, grid, topology);
+    scratch.chunk_entry(chunk_pos).or_insert(chunk);
+  };
+
+  restore_chunks(grid, replaced);
+
+  let mut changed = scratch.chunk_iter()
+    .filter(|&(&chunk_pos, chunk)| match grid.chunk(chunk_pos) {
+      Some(previous) => A::count_changed(chunk, previous) > 0,
+      None => !A::is_chunk_vacant(chunk)
+    })
+    .map(|(&chunk_pos, _)| chunk_pos)
+    .collect::<HashSet<_>>();
+
+  for chunk_pos in skipped {
+    if let Some(chunk) = grid.remove_chunk(chunk_pos) {
+      scratch.chunk_entry(chunk_pos).or_insert(chunk);
+    };
+  };
+
+  let contracted = contracted_chunks(automata, scratch.chunk_iter(), topology);
+  for (&chunk_pos, chunk) in scratch.chunk_iter() {
+    if contracted.contains(&chunk_pos) && !A::is_chunk_vacant(chunk) {
+      changed.insert(chunk_pos);
+    };
+  };
+  scratch.retain_chunks(|chunk_pos, _| !contracted.contains(chunk_pos));
+
+  // Chunks which are no longer present changed if any of their cells were occupied
+  for (&chunk_pos, chunk) in grid.chunk_iter() {
+    if scratch.chunk(chunk_pos).is_none() && !A::is_chunk_vacant(chunk) {
+      changed.insert(chunk_pos);
+    };
+  };
+
+  active.changed = Some(changed);
+  swap(grid, scratch);
+  automata.finish_step();
+}
+
+/// Returns the position of every chunk that the automata expands into within the topology, in the order they are first reached.
+fn expanded_chunks<A>(grid: &A, automata: &mut impl Automata<A>, topology: &Topology<A::Chunk>) -> Vec<ChunkPos>
+where A: ChunkMap {
+  let mut reached = HashSet::new();
+  let mut chunk_positions = Vec::new();
+  for (&chunk_pos, chunk) in grid.chunk_iter() {
+    automata.expansion(chunk).apply_with_center(chunk_pos, |chunk_pos| {
+      if let Some(chunk_pos) = topology.resolve(chunk_pos).filter(|&chunk_pos| reached.insert(chunk_pos)) {
+        chunk_positions.push(chunk_pos);
+      };
+    });
+  };
+
+  chunk_positions
+}
+
+/// Inserts a copy of the chunk that the topology maps each position outside of it to, at every such position
+/// within `reach` chunks of the given chunks. Positions outside of a bounded topology hold its boundary,
+/// so that automata reading cells from the grid read them as they are arranged within the topology.
+/// Returns the chunks which were replaced, so that they can be put back with [`restore_chunks`].
+fn insert_edge_chunks<A>(grid: &mut A, topology: &Topology<A::Chunk>, chunk_positions: &[ChunkPos], reach: u32) -> ReplacedChunks<A::Chunk>
+where A: ChunkMap, A::Chunk: Clone {
+  if let Topology::Infinite = topology {
+    return Vec::new();
+  };
+
+  let reach = i64::from(reach);
+  let mut edges = HashMap::new();
+  for &[x, y] in chunk_positions {
+    for offset_y in -reach..=reach {
+      for offset_x in -reach..=reach {
+        // Positions beyond the range of chunk positions can never be read
+        let Ok(x) = i32::try_from(i64::from(x) + offset_x) else { continue };
+        let Ok(y) = i32::try_from(i64::from(y) + offset_y) else { continue };
+        let resolved = topology.resolve([x, y]);
+        if resolved == Some([x, y]) || edges.contains_key(&[x, y]) {
+          continue;
+        };
+
+        let chunk = match resolved {
+          Some(resolved) => grid.chunk(resolved).cloned(),
+          None => topology.boundary().cloned()
+        };
+        edges.insert([x, y], chunk);
+      };
+    };
+  };
+
+  edges.into_iter()
+    .map(|(chunk_pos, chunk)| {
+      let replaced = match (grid.chunk_entry(chunk_pos), chunk) {
+        (Entry::Occupied(mut entry), Some(chunk)) => Some(std::mem::replace(entry.get_mut(), chunk)),
+        (Entry::Occupied(entry), None) => Some(entry.remove()),
+        (Entry::Vacant(entry), Some(chunk)) => { entry.insert(chunk); None },
+        (Entry::Vacant(_), None) => None
+      };
+      (chunk_pos, replaced)
+    })
+    .collect()
+}
+
+/// Puts back the chunks replaced by [`insert_edge_chunks`].
+fn restore_chunks<A: ChunkMap>(grid: &mut A, replaced: ReplacedChunks<A::Chunk>) {
+  for (chunk_pos, chunk) in replaced {
+    match chunk {
+      Some(chunk) => { grid.chunk_entry(chunk_pos).insert_entry(chunk); },
+      None => { grid.remove_chunk(chunk_pos); }
+    };
+  };
+}
+
+/// Simulates a single chunk of the next step of `grid`.
+fn simulate_chunk<A>(automata: &mut impl Automata<A>, pos: ChunkPos, grid: &A, topology: &Topology<A::Chunk>) -> A::Chunk
+where A: ChunkMap, A::Chunk: Default {
+  let mut chunk = A::Chunk::default();
+  let neighbourhood = topology.neighbourhood(pos, |pos| grid.chunk(pos));
+  if !automata.simulate_chunk(pos, &mut chunk, &neighbourhood, grid) {
+    A::for_each_cell_mut(pos, &mut chunk, |pos, value| *value = automata.simulate(pos, grid));
+  };
+
+  chunk
+}
+
+/// Drops every chunk which the automata's contraction rule allows to be dropped.
+fn contract_chunks<A: ChunkMap>(grid: &mut A, automata: &mut impl Automata<A>, topology: &Topology<A::Chunk>) {
+  let contracted = contracted_chunks(automata, grid.chunk_iter(), topology);
+  grid.retain_chunks(|chunk_pos, _| !contracted.contains(chunk_pos));
+}
+
+/// Compares a grid against the grid of the previous generation, treating missing chunks as entirely vacant.
+fn generation_stats<A>(current: &A, previous: &A) -> GenerationStats
+where A: ChunkMap, A::Cell: PartialEq {
+  let mut stats = GenerationStats::default();
+  for (&chunk_pos, chunk) in current.chunk_iter() {
+    let population = A::count_occupied(chunk);
+    stats.population += population;
+    stats.changed += match previous.chunk(chunk_pos) {
+      Some(previous) => A::count_changed(chunk, previous),
+      None => { stats.chunks_created += 1; population }
+    };
+  };
+
+  for (&chunk_pos, chunk) in previous.chunk_iter() {
+    if current.chunk(chunk_pos).is_none() {
+      stats.chunks_dropped += 1;
+      stats.changed += A::count_occupied(chunk);
+    };
+  };
+
+  stats
+}
+
+/// Returns the positions of every chunk which the automata's contraction rule allows to be dropped,
+/// and which has no neighbouring chunks within the topology that are kept.
+fn contracted_chunks<'a, A>(automata: &mut impl Automata<A>, chunks: impl Iterator<Item = (&'a ChunkPos, &'a A::Chunk)>, topology: &Topology<A::Chunk>) -> HashSet<ChunkPos>
+where A: AutomataAdapter + 'a {
+  let (contractible, kept): (HashSet<ChunkPos>, HashSet<ChunkPos>) = chunks
+    .map(|(&chunk_pos, chunk)| (chunk_pos, automata.contraction(chunk)))
+    .fold(Default::default(), |(mut contractible, mut kept), (chunk_pos, contract)| {
+      if contract { contractible.insert(chunk_pos) } else { kept.insert(chunk_pos) };
+      (contractible, kept)
+    });
+
+  contractible.into_iter()
+    .filter(|&chunk_pos| {
+      Expansion8::rel::<i32>().into_iter().all(|rel| {
+        topology.resolve(Vector2::into_array(Vector2::from_array(chunk_pos) + rel))
+          .is_none_or(|neighbour| !kept.contains(&neighbour))
+      })
+    })
+    .collect()
+}
+
+/// Contraction rule shared by the built-in rules, allowing a chunk to be dropped once every cell in it is vacant.
+fn contract_vacant(chunk: &impl VacantChunk) -> bool {
+  chunk.is_vacant()
+}
+
+/// Chunks which can tell whether every cell in them is vacant without knowing the grid they belong to.
+trait VacantChunk {
+  fn is_vacant(&self) -> bool;
+}
+
+impl<T, const S: usize> VacantChunk for Chunk<T, S>
+where T: Default + PartialEq {
+  fn is_vacant(&self) -> bool {
+    let default = T::default();
+    self.iter().all(|value| *value == default)
+  }
+}
+
+impl<T, const S: usize> VacantChunk for ChunkSparse<T, S> {
+  fn is_vacant(&self) -> bool {
+    self.is_all_vacant()
+  }
+}
+
+/// Steps a grid once, determining every chunk of the next step serially, then simulating them in parallel.
+#[cfg(feature = "multi-thread")]
+fn par_step_chunks<A, U>(
+  grid: &mut A,
+  scratch: &mut A,
+  automata: &mut U,
+  topology: &Topology<A::Chunk>,
+  insert_edges: impl FnOnce(&mut A, &[ChunkPos]) -> ReplacedChunks<A::Chunk>
+) where A: ChunkMap + Sync, A::Chunk: Default + Send + Sync, U: Automata<A> + Clone + Send {
+  let chunk_positions = expanded_chunks(grid, automata, topology);
+  let replaced = insert_edges(grid, &chunk_positions);
+  let grid_ref = &*grid;
+  let chunks = chunk_positions.into_par_iter()
+    .map_with(automata.clone(), |automata, chunk_pos| {
+      (chunk_pos, simulate_chunk(automata, chunk_pos, grid_ref, topology))
+    })
+    .collect::<Vec<_>>();
+
+  restore_chunks(grid, replaced);
+  scratch.clear_chunks();
+  for (chunk_pos, chunk) in chunks {
+    scratch.chunk_entry(chunk_pos).or_insert(chunk);
+  };
+
+  contract_chunks(scratch, automata, topology);
+  swap(grid, scratch);
+  automata.finish_step();
+}
+
+/// Finds every neighbouring chunk containing a cell within `cell_radius` cells of the box between `bounds`.
+fn radius_expansion<const S: usize>(bounds: Option<(LocalPos, LocalPos)>, cell_radius: usize) -> ExpansionSet {
+  let Some((min, max)) = bounds else { return ExpansionSet::default() };
+  let size = S as i64;
+  let cell_radius = i64::try_from(cell_radius).unwrap_or(i64::MAX);
+  // The distance along one axis between the bounds and the chunk at the given offset
+  let gap = |axis: usize, offset: i64| -> i64 {
+    let (min, max) = (min[axis] as i64, max[axis] as i64);
+    let (start, end) = (offset * size, offset * size + size - 1);
+    i64::max(start - max, min - end).max(0)
+  };
+
+  let r = i64::from(chunk_radius::<S>(cell_radius as usize));
+  (-r..=r)
+    .flat_map(|y| (-r..=r).map(move |x| [x, y]))
+    .filter(|&[x, y]| (x != 0 || y != 0) && gap(0, x) <= cell_radius && gap(1, y) <= cell_radius)
+    .map(|offset| offset.map(|v| v as i32))
+    .collect()
 }
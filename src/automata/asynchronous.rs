@@ -0,0 +1,102 @@
+use super::{contract_chunks, Automata, AutomataAdapter, CellRng, ChunkMap, Expansion, Topology};
+use crate::GlobalPos;
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+
+
+/// The order in which cells are updated by an asynchronous step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UpdateOrder {
+  /// Every cell is updated once per step, in an order shuffled by the given generator.
+  /// The generator is advanced after every step, so that each step uses a different order.
+  RandomSequential(CellRng),
+  /// Cells whose coordinates sum to an even number are updated first, followed by the rest,
+  /// so that no cell reads an orthogonal neighbour which has already been updated during the same pass.
+  Checkerboard,
+  /// Cells are updated row by row in order of increasing y, and in order of increasing x within each row.
+  #[default]
+  RowSweep
+}
+
+impl UpdateOrder {
+  /// Creates a random sequential order seeded with `seed`.
+  pub const fn random(seed: u64) -> Self {
+    UpdateOrder::RandomSequential(CellRng::new(seed))
+  }
+
+  fn sort(&self, cells: &mut [GlobalPos]) {
+    match self {
+      UpdateOrder::RandomSequential(rng) => cells.sort_by_cached_key(|&[x, y]| (rng.u64([x, y]), y, x)),
+      UpdateOrder::Checkerboard => cells.sort_unstable_by_key(|&[x, y]| ((x + y).rem_euclid(2), y, x)),
+      UpdateOrder::RowSweep => cells.sort_unstable_by_key(|&[x, y]| (y, x))
+    };
+  }
+}
+
+/// Describes a grid upon which a cellular automata may be stepped asynchronously.
+///
+/// Unlike [`AutomataAdapter::step`], which determines every cell from the previous generation,
+/// an asynchronous step updates cells one at a time in place, so that each cell reads the values
+/// of any cells updated before it during the same step.
+pub trait AsyncAutomataAdapter: AutomataAdapter {
+  /// Steps the automata once, updating every cell of the chunks that a synchronous step would simulate in the given order.
+  ///
+  /// Cells are determined with [`Automata::simulate`] rather than [`Automata::simulate_chunk`],
+  /// and the grid is always treated as infinite.
+  fn step_async(&mut self, automata: &mut impl Automata<Self>, order: &mut UpdateOrder);
+
+  /// Steps the automata asynchronously `n` times.
+  fn step_async_n(&mut self, automata: &mut impl Automata<Self>, order: &mut UpdateOrder, n: usize) {
+    for _ in 0..n {
+      self.step_async(automata, order);
+    };
+  }
+}
+
+impl<T, const S: usize, H> AsyncAutomataAdapter for ExGrid<T, S, H>
+where T: Default + PartialEq, H: BuildHasher {
+  fn step_async(&mut self, automata: &mut impl Automata<Self>, order: &mut UpdateOrder) {
+    step_async(self, automata, order);
+  }
+}
+
+impl<T, const S: usize, H> AsyncAutomataAdapter for ExGridSparse<T, S, H>
+where T: PartialEq, H: BuildHasher {
+  fn step_async(&mut self, automata: &mut impl Automata<Self>, order: &mut UpdateOrder) {
+    step_async(self, automata, order);
+  }
+}
+
+/// Creates every chunk that a synchronous step would simulate, then updates each of their cells in place.
+fn step_async<A>(grid: &mut A, automata: &mut impl Automata<A>, order: &mut UpdateOrder)
+where A: ChunkMap, A::Chunk: Default, A::Cell: PartialEq {
+  let mut chunks = HashSet::new();
+  for (&chunk_pos, chunk) in grid.chunk_iter() {
+    automata.expansion(chunk).apply_with_center(chunk_pos, |chunk_pos| { chunks.insert(chunk_pos); });
+  };
+
+  let size = A::SIZE as i64;
+  let mut cells = Vec::with_capacity(chunks.len() * A::SIZE * A::SIZE);
+  for chunk_pos in chunks {
+    grid.chunk_entry(chunk_pos).or_default();
+    let [min_x, min_y] = chunk_pos.map(|v| i64::from(v) * size);
+    for y in min_y..min_y + size {
+      cells.extend((min_x..min_x + size).map(|x| [x, y]));
+    };
+  };
+
+  order.sort(&mut cells);
+  for pos in cells {
+    let cell = automata.simulate(pos, grid);
+    grid.put_cell(pos, cell);
+  };
+
+  contract_chunks(grid, automata, &Topology::Infinite);
+  automata.finish_step();
+  if let UpdateOrder::RandomSequential(rng) = order {
+    rng.advance();
+  };
+}
@@ -0,0 +1,84 @@
+use super::{AutomataAdapter, ChunkMap};
+use crate::{ChunkPos, GlobalPos};
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+
+
+/// The rule of a block cellular automata, which updates aligned 2x2 blocks of cells at once.
+///
+/// Each step partitions the grid into blocks whose north-west corners lie on even coordinates,
+/// or on odd coordinates when `odd` is true, alternating between the two from step to step (the Margolus neighbourhood).
+/// This allows rules such as Critters, billiard-ball machines, or falling sand to be expressed as permutations of blocks.
+pub trait BlockRule<C> {
+  /// Returns the next state of a block, given and returned in the order `[nw, ne, sw, se]`.
+  fn step_block(&mut self, block: [C; 4], odd: bool) -> [C; 4];
+}
+
+/// Describes a grid upon which a block cellular automata may operate.
+pub trait BlockAutomataAdapter: AutomataAdapter {
+  /// Steps the rule once using the partition selected by `odd`, updating the grid in place.
+  ///
+  /// Only blocks overlapping an existing chunk are updated, so rules which change entirely vacant blocks
+  /// will not affect the rest of the grid. Sparse grids drop any chunks left vacant after the step,
+  /// while dense grids keep every chunk.
+  fn step_blocks(&mut self, rule: &mut impl BlockRule<Self::Cell>, odd: bool);
+
+  /// Steps the rule `n` times, alternating partitions, starting with the partition selected by `odd`.
+  fn step_blocks_n(&mut self, rule: &mut impl BlockRule<Self::Cell>, odd: bool, n: usize) {
+    for i in 0..n {
+      self.step_blocks(rule, odd ^ (i % 2 == 1));
+    };
+  }
+}
+
+impl<T, const S: usize, H> BlockAutomataAdapter for ExGrid<T, S, H>
+where T: Default + PartialEq, H: BuildHasher {
+  fn step_blocks(&mut self, rule: &mut impl BlockRule<T>, odd: bool) {
+    step_blocks(self, rule, odd);
+  }
+}
+
+impl<T, const S: usize, H> BlockAutomataAdapter for ExGridSparse<T, S, H>
+where T: PartialEq, H: BuildHasher {
+  fn step_blocks(&mut self, rule: &mut impl BlockRule<Option<T>>, odd: bool) {
+    step_blocks(self, rule, odd);
+    self.clean_up();
+  }
+}
+
+/// Updates every block overlapping an existing chunk.
+/// Each block is handled by the chunk containing its north-west cell, which may be a chunk that does not exist.
+fn step_blocks<A>(grid: &mut A, rule: &mut impl BlockRule<A::Cell>, odd: bool)
+where A: ChunkMap, A::Cell: PartialEq {
+  let size = A::SIZE as i64;
+  let existing = grid.chunk_iter().map(|(&chunk_pos, _)| chunk_pos).collect::<HashSet<ChunkPos>>();
+  let owners = existing.iter()
+    .flat_map(|&[x, y]| [[x, y], [x - 1, y], [x, y - 1], [x - 1, y - 1]])
+    .collect::<HashSet<ChunkPos>>();
+
+  for [x, y] in owners {
+    // Whether the owner chunk and the chunks to its east, south, and south-east exist
+    let exists = [[x, y], [x + 1, y], [x, y + 1], [x + 1, y + 1]].map(|chunk_pos| existing.contains(&chunk_pos));
+    let min = [i64::from(x) * size, i64::from(y) * size];
+    // The first block within the chunk along each axis
+    let start = min.map(|v| v + (v + odd as i64).rem_euclid(2));
+    for block_y in (start[1]..min[1] + size).step_by(2) {
+      for block_x in (start[0]..min[0] + size).step_by(2) {
+        let [east, south] = [block_x + 1 >= min[0] + size, block_y + 1 >= min[1] + size];
+        let overlaps = exists[0] || (east && exists[1]) || (south && exists[2]) || (east && south && exists[3]);
+        if !overlaps {
+          continue;
+        };
+
+        let positions: [GlobalPos; 4] = [[block_x, block_y], [block_x + 1, block_y], [block_x, block_y + 1], [block_x + 1, block_y + 1]];
+        let block = positions.map(|pos| grid.take_cell(pos));
+        for (pos, cell) in positions.into_iter().zip(rule.step_block(block, odd)) {
+          grid.put_cell(pos, cell);
+        };
+      };
+    };
+  };
+}
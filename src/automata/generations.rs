@@ -0,0 +1,158 @@
+use super::{contract_vacant, Automata, ChunkNeighbourhood, Expansion8};
+use super::life::{neighbourhood, LifeLikeRule, RuleParseError};
+use crate::{ChunkPos, GlobalPos};
+use crate::chunk::{Chunk, ChunkSparse};
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::fmt;
+use std::hash::BuildHasher;
+use std::str::FromStr;
+
+
+
+/// A multi-state extension of life-like rules, such as Brian's Brain (`B2/S/C3`).
+///
+/// Cells in state 0 are dead and cells in state 1 are alive, with births and survival following a [`LifeLikeRule`]
+/// in which only live cells count as neighbours. Instead of dying immediately, a live cell which does not survive
+/// passes through each of the dying states from 2 up to `states - 1`, before becoming dead again.
+///
+/// Rules are parsed from `B.../S.../C...` rulestrings, where the `C` section gives the number of states.
+/// The birth and survival sections accept everything that [`LifeLikeRule`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+  life: LifeLikeRule,
+  states: u16
+}
+
+impl GenerationsRule {
+  /// Brian's Brain, `B2/S/C3`.
+  pub const BRIANS_BRAIN: Self = GenerationsRule::new(LifeLikeRule::totalistic(&[2], &[]), 3);
+
+  /// Creates a rule from the life-like rule followed by live cells, and the total number of states.
+  ///
+  /// # Panics
+  /// Panics if `states` is less than 2 or greater than 256.
+  pub const fn new(life: LifeLikeRule, states: u16) -> Self {
+    assert!(states >= 2 && states <= 256, "number of states must be between 2 and 256");
+    GenerationsRule { life, states }
+  }
+
+  /// The life-like rule followed by live cells.
+  pub const fn life(&self) -> LifeLikeRule {
+    self.life
+  }
+
+  /// The total number of states, including the dead and live states.
+  pub const fn states(&self) -> u16 {
+    self.states
+  }
+
+  /// Returns the next state of a cell given its current state and the neighbourhood of its live neighbours.
+  pub const fn next_state(&self, state: u8, neighbourhood: u8) -> u8 {
+    match state {
+      0 => self.life.births(neighbourhood) as u8,
+      1 if self.life.survives(neighbourhood) => 1,
+      _ if state as u16 + 1 >= self.states => 0,
+      _ => state + 1
+    }
+  }
+
+  fn next_cell(&self, pos: GlobalPos, state: impl Fn(GlobalPos) -> u8) -> u8 {
+    self.next_state(state(pos), neighbourhood(pos, |pos| state(pos) == 1))
+  }
+}
+
+impl FromStr for GenerationsRule {
+  type Err = RuleParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut states = None;
+    let mut sections = Vec::new();
+    for section in s.trim().split('/') {
+      let Some(count) = section.strip_prefix(['C', 'c']) else {
+        sections.push(section);
+        continue;
+      };
+
+      if states.is_some() {
+        return Err(RuleParseError::DuplicateSection('C'));
+      };
+
+      if let Some(c) = count.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(RuleParseError::UnexpectedChar(c));
+      };
+
+      let count = count.bytes().fold(0u32, |count, digit| count.saturating_mul(10).saturating_add(u32::from(digit - b'0')));
+      if !(2..=256).contains(&count) {
+        return Err(RuleParseError::InvalidStates(count));
+      };
+
+      states = Some(count as u16);
+    };
+
+    let states = states.ok_or(RuleParseError::MissingSection('C'))?;
+    Ok(GenerationsRule::new(sections.join("/").parse()?, states))
+  }
+}
+
+impl fmt::Display for GenerationsRule {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}/C{}", self.life, self.states)
+  }
+}
+
+impl<const S: usize, H> Automata<ExGridSparse<u8, S, H>> for GenerationsRule
+where H: BuildHasher {
+  type Expansion = Expansion8;
+
+  /// Only live cells can cause births, so dying cells at the edges of a chunk do not expand.
+  fn expansion(&mut self, chunk: &ChunkSparse<u8, S>) -> Expansion8 {
+    chunk.edges_corners_expansion(|&state| state == 1)
+  }
+
+  fn contraction(&mut self, chunk: &ChunkSparse<u8, S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGridSparse<u8, S, H>) -> Option<u8> {
+    let state = self.next_cell(pos, |pos| grid.get(pos).copied().unwrap_or(0));
+    (state != 0).then_some(state)
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut ChunkSparse<u8, S>, neighbourhood: &ChunkNeighbourhood<'_, ChunkSparse<u8, S>>, _: &ExGridSparse<u8, S, H>) -> bool {
+    for (local, value) in chunk.as_chunk_mut().cells_mut() {
+      let pos = local.map(|v| v as i64);
+      let state = self.next_cell(pos, |pos| neighbourhood.get_cell(pos).copied().unwrap_or(0));
+      *value = (state != 0).then_some(state);
+    };
+
+    true
+  }
+}
+
+impl<const S: usize, H> Automata<ExGrid<u8, S, H>> for GenerationsRule
+where H: BuildHasher {
+  type Expansion = Expansion8;
+
+  /// Only live cells can cause births, so dying cells at the edges of a chunk do not expand.
+  fn expansion(&mut self, chunk: &Chunk<u8, S>) -> Expansion8 {
+    chunk.edges_corners_expansion(|&state| state == 1)
+  }
+
+  fn contraction(&mut self, chunk: &Chunk<u8, S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGrid<u8, S, H>) -> u8 {
+    self.next_cell(pos, |pos| grid.get(pos).copied().unwrap_or(0))
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut Chunk<u8, S>, neighbourhood: &ChunkNeighbourhood<'_, Chunk<u8, S>>, _: &ExGrid<u8, S, H>) -> bool {
+    for (local, value) in chunk.cells_mut() {
+      let pos = local.map(|v| v as i64);
+      *value = self.next_cell(pos, |pos| neighbourhood.get_cell(pos).copied().unwrap_or(0));
+    };
+
+    true
+  }
+}
@@ -0,0 +1,434 @@
+use super::life::{neighbourhood, LifeLikeRule};
+use crate::GlobalPos;
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// The smallest level of the root node, large enough for the root to be stepped.
+const MIN_ROOT_LEVEL: u8 = 3;
+
+/// A position relative to the root, which may lie beyond the range of positions
+/// since the root grows past the live cells it contains.
+type NodePos = [i128; 2];
+
+/// A square quadtree node covering `2^level` cells along each side, built from four nodes of the level below.
+/// Nodes of level 0 are single cells, and are represented by `DEAD` and `ALIVE`.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+  /// The children of this node in the order `[nw, ne, sw, se]`.
+  children: [NodeId; 4],
+  level: u8,
+  population: u64
+}
+
+/// A Hashlife engine, which simulates a [`LifeLikeRule`] on a memoised quadtree.
+///
+/// Identical regions of a pattern are stored only once, and the result of stepping each region is cached,
+/// so patterns with a lot of repetition in space or time can be advanced by enormous numbers of generations.
+/// Unlike stepping a grid chunk by chunk, the engine is fastest when advancing by large powers of two.
+///
+/// Patterns are imported from and exported to [`ExGridSparse<(), S>`] or [`ExGrid<bool, S>`] grids.
+/// Cells may be set anywhere within the range of positions, and patterns may be advanced by up to `u64::MAX` generations in total.
+/// Nodes are never freed while stepping, so long simulations should call [`Hashlife::collect_garbage`] occasionally.
+/// Rules with `B0` are not supported, since they would fill infinite space with live cells.
+#[derive(Debug, Clone)]
+pub struct Hashlife {
+  rule: LifeLikeRule,
+  nodes: Vec<Node>,
+  ids: HashMap<[NodeId; 4], NodeId>,
+  /// The result of stepping a node by `2^j` generations, keyed by the node and `j`.
+  results: HashMap<(NodeId, u8), NodeId>,
+  /// The empty node of each level.
+  empty: Vec<NodeId>,
+  root: NodeId,
+  /// The position of the north-west corner of the root node.
+  origin: NodePos,
+  generation: u64
+}
+
+impl Hashlife {
+  /// Creates an engine with no live cells.
+  ///
+  /// # Panics
+  /// Panics if `rule` contains `B0`.
+  pub fn new(rule: LifeLikeRule) -> Self {
+    assert!(!rule.births(0), "rules with B0 are not supported");
+    let leaf = |population| Node { children: [DEAD; 4], level: 0, population };
+    let mut hashlife = Hashlife {
+      rule,
+      nodes: vec![leaf(0), leaf(1)],
+      ids: HashMap::new(),
+      results: HashMap::new(),
+      empty: vec![DEAD],
+      root: DEAD,
+      origin: [0, 0],
+      generation: 0
+    };
+
+    hashlife.root = hashlife.empty(MIN_ROOT_LEVEL);
+    let half = 1 << (MIN_ROOT_LEVEL - 1);
+    hashlife.origin = [-half, -half];
+    hashlife
+  }
+
+  /// Creates an engine from the positions of live cells.
+  pub fn from_cells(rule: LifeLikeRule, cells: impl IntoIterator<Item = GlobalPos>) -> Self {
+    let mut hashlife = Hashlife::new(rule);
+    for pos in cells {
+      hashlife.set(pos, true);
+    };
+
+    hashlife
+  }
+
+  /// Creates an engine from the occupied cells of a sparse grid.
+  pub fn from_grid_sparse<const S: usize, H>(rule: LifeLikeRule, grid: &ExGridSparse<(), S, H>) -> Self {
+    Hashlife::from_cells(rule, grid.cells().map(|(pos, _)| pos))
+  }
+
+  /// Creates an engine from the live cells of a grid.
+  pub fn from_grid<const S: usize, H>(rule: LifeLikeRule, grid: &ExGrid<bool, S, H>) -> Self {
+    Hashlife::from_cells(rule, grid.cells().filter(|&(_, &alive)| alive).map(|(pos, _)| pos))
+  }
+
+  pub fn rule(&self) -> LifeLikeRule {
+    self.rule
+  }
+
+  /// The number of generations that have been simulated.
+  pub fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  /// The number of live cells.
+  pub fn population(&self) -> u64 {
+    self.node(self.root).population
+  }
+
+  /// Returns the state of the cell at `pos`.
+  pub fn get(&self, pos: GlobalPos) -> bool {
+    let level = self.node(self.root).level;
+    let [x, y] = self.relative(pos);
+    let size = 1 << level;
+    if !(0..size).contains(&x) || !(0..size).contains(&y) {
+      return false;
+    };
+
+    let mut id = self.root;
+    for level in (0..level).rev() {
+      id = self.node(id).children[quadrant([x, y], level)];
+    };
+
+    id == ALIVE
+  }
+
+  /// Sets the state of the cell at `pos`, growing the tree if needed.
+  pub fn set(&mut self, pos: GlobalPos, alive: bool) {
+    loop {
+      let size = 1 << self.node(self.root).level;
+      let [x, y] = self.relative(pos);
+      if (0..size).contains(&x) && (0..size).contains(&y) {
+        self.root = self.set_node(self.root, [x, y], alive);
+        return;
+      };
+
+      self.expand();
+    };
+  }
+
+  /// Advances the pattern by `2^log2` generations.
+  ///
+  /// # Panics
+  /// Panics if `log2` is 64 or more, if the total number of generations would overflow a `u64`,
+  /// or if a live cell would move beyond the range of positions.
+  pub fn step_pow2(&mut self, log2: u8) {
+    assert!(log2 < u64::BITS as u8, "cannot step by 2^{log2} generations");
+    let generation = self.generation.checked_add(1 << log2).expect("generation count overflowed");
+    // The pattern must lie within the central quarter of a root large enough to step by 2^log2,
+    // then one further expansion ensures that it cannot grow past the edges of the result
+    while self.node(self.root).level < log2 + 2 || !self.is_centered() {
+      self.expand();
+    };
+    self.expand();
+
+    let level = self.node(self.root).level;
+    let quarter = 1 << (level - 2);
+    self.root = self.successor(self.root, log2);
+    self.origin = [self.origin[0] + quarter, self.origin[1] + quarter];
+    self.generation = generation;
+    self.shrink();
+    assert!(self.is_within_positions(), "a live cell moved beyond the range of positions");
+  }
+
+  /// Advances the pattern by `generations` generations, stepping by each power of two it is made of.
+  ///
+  /// # Panics
+  /// Panics if the total number of generations would overflow a `u64`,
+  /// or if a live cell would move beyond the range of positions.
+  pub fn step(&mut self, generations: u64) {
+    assert!(self.generation.checked_add(generations).is_some(), "generation count overflowed");
+    for log2 in 0..u64::BITS as u8 {
+      if generations & (1 << log2) != 0 {
+        self.step_pow2(log2);
+      };
+    };
+  }
+
+  /// Returns the positions of every live cell.
+  pub fn cells(&self) -> Vec<GlobalPos> {
+    let mut cells = Vec::with_capacity(self.population() as usize);
+    self.collect_cells(self.root, self.origin, &mut cells);
+    cells
+  }
+
+  /// Exports the live cells into a new sparse grid.
+  pub fn to_grid_sparse<const S: usize, H>(&self) -> ExGridSparse<(), S, H>
+  where H: BuildHasher + Default {
+    self.cells().into_iter().map(|pos| (pos, ())).collect()
+  }
+
+  /// Exports the live cells into a new grid.
+  pub fn to_grid<const S: usize, H>(&self) -> ExGrid<bool, S, H>
+  where H: BuildHasher + Default {
+    self.cells().into_iter().map(|pos| (pos, true)).collect()
+  }
+
+  /// Discards every cached node and result which is no longer part of the current pattern.
+  pub fn collect_garbage(&mut self) {
+    let mut hashlife = Hashlife::new(self.rule);
+    let mut copied = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+    hashlife.root = hashlife.copy_node(self, self.root, &mut copied);
+    hashlife.origin = self.origin;
+    hashlife.generation = self.generation;
+    *self = hashlife;
+  }
+
+  fn node(&self, id: NodeId) -> &Node {
+    &self.nodes[id as usize]
+  }
+
+  /// Returns the position of a cell relative to the north-west corner of the root.
+  fn relative(&self, pos: GlobalPos) -> NodePos {
+    [i128::from(pos[0]) - self.origin[0], i128::from(pos[1]) - self.origin[1]]
+  }
+
+  /// Returns true if every live cell lies within the range of positions.
+  fn is_within_positions(&self) -> bool {
+    let range = [i128::from(i64::MIN), i128::from(i64::MAX)];
+    self.population_within(self.root, self.origin, range) == self.population()
+  }
+
+  /// Counts the live cells of a node with its north-west corner at `origin`, which lie between `min` and `max` on both axes.
+  fn population_within(&self, id: NodeId, origin: NodePos, [min, max]: [i128; 2]) -> u64 {
+    let node = self.node(id);
+    let last = origin.map(|v| v + (1 << node.level) - 1);
+    if node.population == 0 || origin.iter().any(|&v| v > max) || last.iter().any(|&v| v < min) {
+      return 0;
+    };
+
+    if origin.iter().all(|&v| v >= min) && last.iter().all(|&v| v <= max) {
+      return node.population;
+    };
+
+    let half = 1 << (node.level - 1);
+    node.children.iter().enumerate()
+      .map(|(i, &child)| {
+        let origin = [origin[0] + (i % 2) as i128 * half, origin[1] + (i / 2) as i128 * half];
+        self.population_within(child, origin, [min, max])
+      })
+      .sum()
+  }
+
+  /// Returns the canonical node with the given children.
+  fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+    if let Some(&id) = self.ids.get(&children) {
+      return id;
+    };
+
+    let id = NodeId::try_from(self.nodes.len()).expect("too many nodes");
+    let level = self.node(children[0]).level + 1;
+    let population = children.iter().map(|&child| self.node(child).population).sum();
+    self.nodes.push(Node { children, level, population });
+    self.ids.insert(children, id);
+    id
+  }
+
+  fn empty(&mut self, level: u8) -> NodeId {
+    while self.empty.len() <= level as usize {
+      let empty = *self.empty.last().expect("level 0 is always present");
+      let id = self.join([empty; 4]);
+      self.empty.push(id);
+    };
+
+    self.empty[level as usize]
+  }
+
+  fn set_node(&mut self, id: NodeId, [x, y]: NodePos, alive: bool) -> NodeId {
+    let node = *self.node(id);
+    if node.level == 0 {
+      return if alive { ALIVE } else { DEAD };
+    };
+
+    let level = node.level - 1;
+    let i = quadrant([x, y], level);
+    let mut children = node.children;
+    let mask = (1 << level) - 1;
+    children[i] = self.set_node(children[i], [x & mask, y & mask], alive);
+    self.join(children)
+  }
+
+  /// Doubles the size of the root, keeping the pattern in the middle.
+  fn expand(&mut self) {
+    let root = *self.node(self.root);
+    let e = self.empty(root.level - 1);
+    let [nw, ne, sw, se] = root.children;
+    let children = [
+      self.join([e, e, e, nw]),
+      self.join([e, e, ne, e]),
+      self.join([e, sw, e, e]),
+      self.join([se, e, e, e])
+    ];
+
+    self.root = self.join(children);
+    let half = 1 << (root.level - 1);
+    self.origin = [self.origin[0] - half, self.origin[1] - half];
+  }
+
+  /// Halves the size of the root while the pattern lies entirely within its central quarter.
+  fn shrink(&mut self) {
+    while self.node(self.root).level > MIN_ROOT_LEVEL && self.is_centered() {
+      let level = self.node(self.root).level;
+      self.root = self.center(self.root);
+      let quarter = 1 << (level - 2);
+      self.origin = [self.origin[0] + quarter, self.origin[1] + quarter];
+    };
+  }
+
+  /// Returns true if every live cell of the root lies within its central quarter.
+  fn is_centered(&self) -> bool {
+    let root = self.node(self.root);
+    let inner = root.children.iter().enumerate()
+      .map(|(i, &child)| self.node(self.node(child).children[3 - i]).population)
+      .sum::<u64>();
+    inner == root.population
+  }
+
+  /// Returns the central node of the level below.
+  fn center(&mut self, id: NodeId) -> NodeId {
+    let [nw, ne, sw, se] = self.node(id).children.map(|child| self.node(child).children);
+    self.join([nw[3], ne[2], sw[1], se[0]])
+  }
+
+  /// Returns the central node of the level below `id`, advanced by `2^j` generations.
+  /// `j` must be at most `level - 2`.
+  fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+    let node = *self.node(id);
+    debug_assert!(node.level >= 2 && j <= node.level - 2);
+    if node.population == 0 {
+      return self.empty(node.level - 1);
+    };
+
+    if let Some(&result) = self.results.get(&(id, j)) {
+      return result;
+    };
+
+    let result = if node.level == 2 {
+      self.step_leaf(id)
+    } else {
+      let [nw, ne, sw, se] = node.children.map(|child| self.node(child).children);
+      // Nine overlapping nodes of the level below, arranged in a 3x3 grid
+      let overlapping = [
+        node.children[0], self.join([nw[1], ne[0], nw[3], ne[2]]), node.children[1],
+        self.join([nw[2], nw[3], sw[0], sw[1]]), self.join([nw[3], ne[2], sw[1], se[0]]), self.join([ne[2], ne[3], se[0], se[1]]),
+        node.children[2], self.join([sw[1], se[0], sw[3], se[2]]), node.children[3]
+      ];
+
+      let full_speed = j == node.level - 2;
+      let mut stepped = [DEAD; 9];
+      for (stepped, overlapping) in stepped.iter_mut().zip(overlapping) {
+        *stepped = if full_speed { self.successor(overlapping, j - 1) } else { self.center(overlapping) };
+      };
+
+      let j = if full_speed { j - 1 } else { j };
+      let mut children = [DEAD; 4];
+      for (i, child) in children.iter_mut().enumerate() {
+        let [x, y] = [i % 2, i / 2];
+        let at = |dx: usize, dy: usize| stepped[(y + dy) * 3 + x + dx];
+        let quadrant = self.join([at(0, 0), at(1, 0), at(0, 1), at(1, 1)]);
+        *child = self.successor(quadrant, j);
+      };
+
+      self.join(children)
+    };
+
+    self.results.insert((id, j), result);
+    result
+  }
+
+  /// Steps the central 2x2 cells of a 4x4 node by one generation.
+  fn step_leaf(&mut self, id: NodeId) -> NodeId {
+    let mut cells = [[false; 4]; 4];
+    for (i, &child) in self.node(id).children.iter().enumerate() {
+      for (j, &cell) in self.node(child).children.iter().enumerate() {
+        cells[(i / 2) * 2 + j / 2][(i % 2) * 2 + j % 2] = cell == ALIVE;
+      };
+    };
+
+    let is_alive = |[x, y]: GlobalPos| cells[y as usize][x as usize];
+    let next = [[1, 1], [2, 1], [1, 2], [2, 2]].map(|pos| {
+      let alive = self.rule.next_state(is_alive(pos), neighbourhood(pos, is_alive));
+      if alive { ALIVE } else { DEAD }
+    });
+
+    self.join(next)
+  }
+
+  fn collect_cells(&self, id: NodeId, origin: NodePos, cells: &mut Vec<GlobalPos>) {
+    let node = self.node(id);
+    if node.population == 0 {
+      return;
+    };
+
+    if node.level == 0 {
+      // Live cells always lie within the range of positions
+      cells.push(origin.map(|v| v as i64));
+      return;
+    };
+
+    let half = 1 << (node.level - 1);
+    for (i, &child) in node.children.iter().enumerate() {
+      let origin = [origin[0] + (i % 2) as i128 * half, origin[1] + (i / 2) as i128 * half];
+      self.collect_cells(child, origin, cells);
+    };
+  }
+
+  fn copy_node(&mut self, from: &Hashlife, id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+    if let Some(&id) = copied.get(&id) {
+      return id;
+    };
+
+    let children = from.node(id).children.map(|child| self.copy_node(from, child, copied));
+    let new_id = self.join(children);
+    copied.insert(id, new_id);
+    new_id
+  }
+}
+
+impl Default for Hashlife {
+  fn default() -> Self {
+    Hashlife::new(LifeLikeRule::default())
+  }
+}
+
+/// Returns the index of the child containing the position `[x, y]` relative to a node whose children are of the given level.
+fn quadrant([x, y]: NodePos, level: u8) -> usize {
+  ((x >> level) & 1) as usize | (((y >> level) & 1) as usize) << 1
+}
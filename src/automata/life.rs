@@ -0,0 +1,376 @@
+use super::{contract_vacant, Automata, ChunkNeighbourhood, Expansion8};
+use crate::{ChunkPos, GlobalPos};
+use crate::chunk::{Chunk, ChunkSparse};
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::fmt;
+use std::hash::BuildHasher;
+use std::str::FromStr;
+
+
+
+/// The relative positions of the eight neighbours of a cell, in the order of the bits of a neighbourhood.
+/// A neighbourhood is a `u8` where bit `i` is set if the neighbour at `NEIGHBOURS[i]` is alive.
+pub const NEIGHBOURS: [[i64; 2]; 8] = [
+  [-1, -1], [0, -1], [1, -1],
+  [-1, 0], [1, 0],
+  [-1, 1], [0, 1], [1, 1]
+];
+
+/// Hensel letters valid for each neighbour count from 0 to 4, along with a representative neighbourhood of each.
+/// Counts 5 to 7 use the letters of `8 - count`, with the representative neighbourhood inverted.
+const HENSEL_LETTERS: [&[(char, u8)]; 5] = [
+  &[],
+  &[('c', 0x01), ('e', 0x02)],
+  &[('c', 0x05), ('e', 0x0a), ('a', 0x03), ('i', 0x18), ('k', 0x11), ('n', 0x24)],
+  &[
+    ('c', 0x25), ('e', 0x1a), ('a', 0x0b), ('i', 0x07), ('k', 0x32),
+    ('n', 0x0d), ('j', 0x0e), ('q', 0x26), ('r', 0x19), ('y', 0x31)
+  ],
+  &[
+    ('c', 0xa5), ('e', 0x5a), ('a', 0x0f), ('i', 0x1d), ('k', 0x33), ('n', 0x27), ('j', 0x3a),
+    ('q', 0x36), ('r', 0x1b), ('y', 0x35), ('t', 0x39), ('w', 0x2e), ('z', 0x3c)
+  ]
+];
+
+/// Returns the neighbourhood of the cell at `pos`, using `is_alive` to read each of its neighbours.
+pub fn neighbourhood(pos: GlobalPos, mut is_alive: impl FnMut(GlobalPos) -> bool) -> u8 {
+  NEIGHBOURS.iter().enumerate().fold(0, |neighbourhood, (i, offset)| {
+    let neighbour = [pos[0] + offset[0], pos[1] + offset[1]];
+    neighbourhood | (is_alive(neighbour) as u8) << i
+  })
+}
+
+/// A two-state cellular automata rule in which cells are born or survive based on their eight neighbours,
+/// such as Conway's Game of Life (`B3/S23`).
+///
+/// Rules are parsed from `B.../S...` rulestrings, where each digit lists a neighbour count under which cells are born or survive.
+/// Digits may be followed by Hensel letters to select only some arrangements of that many neighbours (as in `B2ce3/S23`),
+/// or by a `-` and letters to select every arrangement except those (as in `B3/S2-a3`), making the rule isotropic non-totalistic.
+///
+/// Rules with `B0` are accepted, but births only occur within chunks that already exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeLikeRule {
+  birth: NeighbourhoodSet,
+  survival: NeighbourhoodSet
+}
+
+impl LifeLikeRule {
+  /// Conway's Game of Life, `B3/S23`.
+  pub const CONWAY: Self = LifeLikeRule {
+    birth: NeighbourhoodSet::totalistic(&[3]),
+    survival: NeighbourhoodSet::totalistic(&[2, 3])
+  };
+
+  /// Creates an outer-totalistic rule from the neighbour counts under which cells are born or survive.
+  ///
+  /// # Panics
+  /// Panics if any count is greater than 8.
+  pub const fn totalistic(birth: &[u8], survival: &[u8]) -> Self {
+    LifeLikeRule {
+      birth: NeighbourhoodSet::totalistic(birth),
+      survival: NeighbourhoodSet::totalistic(survival)
+    }
+  }
+
+  /// Returns true if a dead cell with the given neighbourhood is born.
+  pub const fn births(&self, neighbourhood: u8) -> bool {
+    self.birth.contains(neighbourhood)
+  }
+
+  /// Returns true if a live cell with the given neighbourhood survives.
+  pub const fn survives(&self, neighbourhood: u8) -> bool {
+    self.survival.contains(neighbourhood)
+  }
+
+  /// Returns the next state of a cell given its current state and neighbourhood.
+  pub const fn next_state(&self, alive: bool, neighbourhood: u8) -> bool {
+    if alive { self.survives(neighbourhood) } else { self.births(neighbourhood) }
+  }
+
+  /// Returns true if this rule only depends on the number of live neighbours, not their arrangement.
+  pub fn is_totalistic(&self) -> bool {
+    self.birth.is_totalistic() && self.survival.is_totalistic()
+  }
+
+  fn next_cell(&self, pos: GlobalPos, is_alive: impl Fn(GlobalPos) -> bool) -> bool {
+    self.next_state(is_alive(pos), neighbourhood(pos, is_alive))
+  }
+}
+
+impl Default for LifeLikeRule {
+  #[inline]
+  fn default() -> Self {
+    Self::CONWAY
+  }
+}
+
+impl FromStr for LifeLikeRule {
+  type Err = RuleParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut birth = None;
+    let mut survival = None;
+    let mut chars = s.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+      let (section, name) = match c.to_ascii_uppercase() {
+        'B' => (&mut birth, 'B'),
+        'S' => (&mut survival, 'S'),
+        '/' if birth.is_some() || survival.is_some() => continue,
+        _ => return Err(RuleParseError::UnexpectedChar(c))
+      };
+
+      if section.is_some() {
+        return Err(RuleParseError::DuplicateSection(name));
+      };
+
+      let mut set = NeighbourhoodSet::EMPTY;
+      while let Some(count) = chars.next_if(char::is_ascii_digit) {
+        let count = count.to_digit(10).unwrap() as u8;
+        if count > 8 {
+          return Err(RuleParseError::InvalidCount(count));
+        };
+
+        let negate = chars.next_if_eq(&'-').is_some();
+        let mut classes = NeighbourhoodSet::EMPTY;
+        while let Some(letter) = chars.next_if(|&c| c.is_ascii_lowercase() && c != 'b' && c != 's') {
+          let class = NeighbourhoodSet::hensel(count, letter)
+            .ok_or(RuleParseError::InvalidLetter(count, letter))?;
+          classes = classes.union(class);
+        };
+
+        let count_set = NeighbourhoodSet::totalistic(&[count]);
+        set = set.union(match (negate, classes.is_empty()) {
+          (false, true) => count_set,
+          (false, false) => classes,
+          (true, _) => count_set.difference(classes)
+        });
+      };
+
+      *section = Some(set);
+    };
+
+    Ok(LifeLikeRule {
+      birth: birth.ok_or(RuleParseError::MissingSection('B'))?,
+      survival: survival.ok_or(RuleParseError::MissingSection('S'))?
+    })
+  }
+}
+
+impl fmt::Display for LifeLikeRule {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "B{}/S{}", self.birth, self.survival)
+  }
+}
+
+impl<const S: usize, H> Automata<ExGridSparse<(), S, H>> for LifeLikeRule
+where H: BuildHasher {
+  type Expansion = Expansion8;
+
+  fn expansion(&mut self, chunk: &ChunkSparse<(), S>) -> Expansion8 {
+    chunk.edges_corners_expansion(|_| true)
+  }
+
+  fn contraction(&mut self, chunk: &ChunkSparse<(), S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGridSparse<(), S, H>) -> Option<()> {
+    self.next_cell(pos, |pos| grid.get(pos).is_some()).then_some(())
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut ChunkSparse<(), S>, neighbourhood: &ChunkNeighbourhood<'_, ChunkSparse<(), S>>, _: &ExGridSparse<(), S, H>) -> bool {
+    for (local, value) in chunk.as_chunk_mut().cells_mut() {
+      let pos = local.map(|v| v as i64);
+      *value = self.next_cell(pos, |pos| neighbourhood.get_cell(pos).is_some()).then_some(());
+    };
+
+    true
+  }
+}
+
+impl<const S: usize, H> Automata<ExGrid<bool, S, H>> for LifeLikeRule
+where H: BuildHasher {
+  type Expansion = Expansion8;
+
+  fn expansion(&mut self, chunk: &Chunk<bool, S>) -> Expansion8 {
+    chunk.edges_corners_expansion(|&alive| alive)
+  }
+
+  fn contraction(&mut self, chunk: &Chunk<bool, S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGrid<bool, S, H>) -> bool {
+    self.next_cell(pos, |pos| grid.get(pos).copied().unwrap_or(false))
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut Chunk<bool, S>, neighbourhood: &ChunkNeighbourhood<'_, Chunk<bool, S>>, _: &ExGrid<bool, S, H>) -> bool {
+    for (local, value) in chunk.cells_mut() {
+      let pos = local.map(|v| v as i64);
+      *value = self.next_cell(pos, |pos| neighbourhood.get_cell(pos).copied().unwrap_or(false));
+    };
+
+    true
+  }
+}
+
+/// An error which can be returned when parsing a [`LifeLikeRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleParseError {
+  /// A character was found where it was not expected.
+  UnexpectedChar(char),
+  /// A neighbour count was greater than 8.
+  InvalidCount(u8),
+  /// A Hensel letter does not exist for the neighbour count it followed.
+  InvalidLetter(u8, char),
+  /// A section of the rulestring, such as birth (`B`) or survival (`S`), was missing.
+  MissingSection(char),
+  /// A section of the rulestring, such as birth (`B`) or survival (`S`), appeared twice.
+  DuplicateSection(char),
+  /// The number of states of a multi-state rule was less than 2 or greater than 256.
+  InvalidStates(u32),
+  /// The rulestring does not name a known rule.
+  UnknownRule
+}
+
+impl fmt::Display for RuleParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      RuleParseError::UnexpectedChar(c) => write!(f, "unexpected character {c:?} in rulestring"),
+      RuleParseError::InvalidCount(count) => write!(f, "neighbour count {count} is greater than 8"),
+      RuleParseError::InvalidLetter(count, letter) => write!(f, "letter {letter:?} is not valid for neighbour count {count}"),
+      RuleParseError::MissingSection(section) => write!(f, "rulestring is missing the {section:?} section"),
+      RuleParseError::DuplicateSection(section) => write!(f, "rulestring contains the {section:?} section twice"),
+      RuleParseError::InvalidStates(states) => write!(f, "number of states {states} is not between 2 and 256"),
+      RuleParseError::UnknownRule => f.write_str("rulestring does not name a known rule")
+    }
+  }
+}
+
+impl std::error::Error for RuleParseError {}
+
+/// A set of neighbourhoods, one bit for each of the 256 possible neighbourhoods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct NeighbourhoodSet([u64; 4]);
+
+impl NeighbourhoodSet {
+  const EMPTY: Self = NeighbourhoodSet([0; 4]);
+
+  const fn totalistic(counts: &[u8]) -> Self {
+    let mut set = Self::EMPTY;
+    let mut neighbourhood = 0;
+    while neighbourhood < 256 {
+      let count = (neighbourhood as u8).count_ones() as u8;
+      let mut i = 0;
+      while i < counts.len() {
+        assert!(counts[i] <= 8, "neighbour count is greater than 8");
+        if counts[i] == count {
+          set.0[neighbourhood / 64] |= 1 << (neighbourhood % 64);
+        };
+
+        i += 1;
+      };
+
+      neighbourhood += 1;
+    };
+
+    set
+  }
+
+  /// Returns every neighbourhood described by a count and Hensel letter,
+  /// which are all of the rotations and reflections of its representative.
+  fn hensel(count: u8, letter: char) -> Option<Self> {
+    let (letters, invert) = match count {
+      0..=4 => (HENSEL_LETTERS[count as usize], false),
+      5..=7 => (HENSEL_LETTERS[8 - count as usize], true),
+      _ => return None
+    };
+
+    let &(_, neighbourhood) = letters.iter().find(|&&(l, _)| l == letter)?;
+    let neighbourhood = if invert { !neighbourhood } else { neighbourhood };
+    Some(symmetries(neighbourhood).into_iter().fold(Self::EMPTY, |set, n| set.with(n)))
+  }
+
+  const fn contains(&self, neighbourhood: u8) -> bool {
+    self.0[neighbourhood as usize / 64] >> (neighbourhood % 64) & 1 != 0
+  }
+
+  fn with(mut self, neighbourhood: u8) -> Self {
+    self.0[neighbourhood as usize / 64] |= 1 << (neighbourhood % 64);
+    self
+  }
+
+  fn is_empty(&self) -> bool {
+    *self == Self::EMPTY
+  }
+
+  fn union(self, other: Self) -> Self {
+    NeighbourhoodSet(std::array::from_fn(|i| self.0[i] | other.0[i]))
+  }
+
+  fn intersection(self, other: Self) -> Self {
+    NeighbourhoodSet(std::array::from_fn(|i| self.0[i] & other.0[i]))
+  }
+
+  fn difference(self, other: Self) -> Self {
+    NeighbourhoodSet(std::array::from_fn(|i| self.0[i] & !other.0[i]))
+  }
+
+  fn is_totalistic(&self) -> bool {
+    (0..=8).all(|count| {
+      let count_set = Self::totalistic(&[count]);
+      let contained = self.intersection(count_set);
+      contained.is_empty() || contained == count_set
+    })
+  }
+}
+
+impl fmt::Display for NeighbourhoodSet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for count in 0..=8 {
+      let count_set = Self::totalistic(&[count]);
+      let contained = self.intersection(count_set);
+      if contained.is_empty() {
+        continue;
+      };
+
+      write!(f, "{count}")?;
+      if contained == count_set {
+        continue;
+      };
+
+      let letters = HENSEL_LETTERS[usize::from(count.min(8 - count))].iter().map(|&(letter, _)| letter);
+      let (included, excluded): (String, String) = letters.map(|letter| {
+        let class = Self::hensel(count, letter).expect("letter should be valid for the count");
+        (letter, class.intersection(contained) == class)
+      }).fold(Default::default(), |(mut included, mut excluded), (letter, is_included)| {
+        if is_included { included.push(letter) } else { excluded.push(letter) };
+        (included, excluded)
+      });
+
+      if excluded.len() < included.len() {
+        write!(f, "-{excluded}")?;
+      } else {
+        write!(f, "{included}")?;
+      };
+    };
+
+    Ok(())
+  }
+}
+
+/// Returns the neighbourhood under each of the eight rotations and reflections of a square.
+fn symmetries(neighbourhood: u8) -> [u8; 8] {
+  let transforms: [fn([i64; 2]) -> [i64; 2]; 8] = [
+    |[x, y]| [x, y], |[x, y]| [-y, x], |[x, y]| [-x, -y], |[x, y]| [y, -x],
+    |[x, y]| [-x, y], |[x, y]| [x, -y], |[x, y]| [y, x], |[x, y]| [-y, -x]
+  ];
+
+  transforms.map(|transform| {
+    NEIGHBOURS.iter().enumerate()
+      .filter(|&(i, _)| neighbourhood >> i & 1 != 0)
+      .map(|(_, &offset)| NEIGHBOURS.iter().position(|&n| n == transform(offset)).unwrap())
+      .fold(0, |out, i| out | 1 << i)
+  })
+}
@@ -0,0 +1,104 @@
+use super::{Automata, AutomataAdapter};
+use crate::GlobalPos;
+use crate::grid::ExGridSparse;
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+
+
+/// A generation of a pattern that is a translated copy of an earlier generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Repetition {
+  /// The earliest generation which was repeated.
+  pub first: usize,
+  /// The number of generations between repeats.
+  pub period: usize,
+  /// How far the pattern moves over one period.
+  pub displacement: GlobalPos
+}
+
+impl Repetition {
+  /// Returns true if the pattern never changes.
+  pub fn is_still(&self) -> bool {
+    self.period == 1 && self.displacement == [0, 0]
+  }
+
+  /// Returns true if the pattern returns to the same position after each period, including still patterns.
+  pub fn is_oscillator(&self) -> bool {
+    self.displacement == [0, 0]
+  }
+
+  /// Returns true if the pattern moves after each period.
+  pub fn is_spaceship(&self) -> bool {
+    self.displacement != [0, 0]
+  }
+}
+
+/// A history of the canonical hashes of each generation of a pattern, used to detect when a generation repeats.
+///
+/// Generations are compared using [`ExGridSparse::canonical_hash`], so two different patterns
+/// which happen to share a hash will be mistaken for a repetition.
+#[derive(Debug, Clone, Default)]
+pub struct PatternHistory {
+  hashes: HashMap<u64, (usize, GlobalPos)>,
+  generation: usize
+}
+
+impl PatternHistory {
+  pub fn new() -> Self {
+    PatternHistory::default()
+  }
+
+  /// The number of generations that have been recorded.
+  pub fn generation(&self) -> usize {
+    self.generation
+  }
+
+  pub fn clear(&mut self) {
+    self.hashes.clear();
+    self.generation = 0;
+  }
+
+  /// Records `grid` as the next generation, returning a repetition if it is a translated copy of an earlier generation.
+  pub fn record<T: Hash, const S: usize, H>(&mut self, grid: &ExGridSparse<T, S, H>) -> Option<Repetition> {
+    let hash = grid.canonical_hash();
+    let min = grid.occupied_bounds().map_or([0, 0], |(min, _)| min);
+    let generation = self.generation;
+    self.generation += 1;
+    match self.hashes.get(&hash) {
+      Some(&(first, first_min)) => Some(Repetition {
+        first,
+        period: generation - first,
+        displacement: [min[0] - first_min[0], min[1] - first_min[1]]
+      }),
+      None => {
+        self.hashes.insert(hash, (generation, min));
+        None
+      }
+    }
+  }
+
+  /// Steps `grid` until one of its generations repeats, recording every generation including the current one.
+  /// Returns `None` if no repetition is found within `max_generations` steps.
+  pub fn run<T, const S: usize, H>(
+    &mut self,
+    grid: &mut ExGridSparse<T, S, H>,
+    automata: &mut impl Automata<ExGridSparse<T, S, H>>,
+    max_generations: usize
+  ) -> Option<Repetition> where T: Hash, H: BuildHasher + Default {
+    if let Some(repetition) = self.record(grid) {
+      return Some(repetition);
+    };
+
+    let mut scratch = ExGridSparse::default();
+    for _ in 0..max_generations {
+      grid.step_scratch(&mut scratch, automata);
+      if let Some(repetition) = self.record(grid) {
+        return Some(repetition);
+      };
+    };
+
+    None
+  }
+}
@@ -0,0 +1,118 @@
+use crate::GlobalPos;
+
+use std::iter::FusedIterator;
+
+
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A counter-based random number generator, whose values are determined entirely by a seed, a generation, and a cell position.
+///
+/// Since no state is shared between cells, the values drawn while simulating a cell do not depend on the order
+/// in which chunks are simulated, so stochastic automata remain reproducible even when stepped in parallel.
+/// Automata should hold a `CellRng` and call [`CellRng::advance`] from [`Automata::finish_step`](super::Automata::finish_step).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CellRng {
+  seed: u64,
+  generation: u64
+}
+
+impl CellRng {
+  pub const fn new(seed: u64) -> Self {
+    CellRng { seed, generation: 0 }
+  }
+
+  pub const fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  /// The generation that values are currently drawn for.
+  pub const fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  pub fn set_generation(&mut self, generation: u64) {
+    self.generation = generation;
+  }
+
+  /// Moves on to the next generation, so that every cell draws new values.
+  pub fn advance(&mut self) {
+    self.generation = self.generation.wrapping_add(1);
+  }
+
+  /// Returns the `index`th random value of the cell at `pos` in the current generation.
+  pub fn u64_indexed(&self, pos: GlobalPos, index: u64) -> u64 {
+    [self.generation, pos[0] as u64, pos[1] as u64, index]
+      .into_iter()
+      .fold(mix(self.seed), |state, value| mix(state.wrapping_add(GOLDEN_GAMMA) ^ value))
+  }
+
+  /// Returns the first random value of the cell at `pos` in the current generation.
+  pub fn u64(&self, pos: GlobalPos) -> u64 {
+    self.u64_indexed(pos, 0)
+  }
+
+  /// Returns a random value in the range `[0, 1)` for the cell at `pos` in the current generation.
+  pub fn f64(&self, pos: GlobalPos) -> f64 {
+    to_f64(self.u64(pos))
+  }
+
+  /// Returns true with the given probability for the cell at `pos` in the current generation.
+  pub fn chance(&self, pos: GlobalPos, probability: f64) -> bool {
+    self.f64(pos) < probability
+  }
+
+  /// Returns an iterator over every random value of the cell at `pos` in the current generation,
+  /// for rules which need to draw more than one value per cell.
+  pub fn stream(&self, pos: GlobalPos) -> CellRngStream {
+    CellRngStream { rng: *self, pos, index: 0 }
+  }
+}
+
+/// An iterator over the random values of a single cell, created by [`CellRng::stream`].
+#[derive(Debug, Clone)]
+pub struct CellRngStream {
+  rng: CellRng,
+  pos: GlobalPos,
+  index: u64
+}
+
+impl CellRngStream {
+  /// Returns the next random value in the range `[0, 1)`.
+  pub fn next_f64(&mut self) -> f64 {
+    to_f64(self.next_u64())
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    let value = self.rng.u64_indexed(self.pos, self.index);
+    self.index = self.index.wrapping_add(1);
+    value
+  }
+}
+
+impl Iterator for CellRngStream {
+  type Item = u64;
+
+  #[inline]
+  fn next(&mut self) -> Option<u64> {
+    Some(self.next_u64())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (usize::MAX, None)
+  }
+}
+
+impl FusedIterator for CellRngStream {}
+
+/// The SplitMix64 finaliser, which scrambles every bit of its input into every bit of its output.
+fn mix(mut z: u64) -> u64 {
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  z ^ (z >> 31)
+}
+
+fn to_f64(value: u64) -> f64 {
+  (value >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
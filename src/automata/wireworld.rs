@@ -0,0 +1,123 @@
+use super::{contract_vacant, Automata, ChunkNeighbourhood, Expansion4};
+use super::life::{RuleParseError, NEIGHBOURS};
+use crate::{ChunkPos, GlobalPos};
+use crate::chunk::{Chunk, ChunkSparse};
+use crate::grid::{ExGrid, ExGridSparse};
+
+use std::fmt;
+use std::hash::BuildHasher;
+use std::str::FromStr;
+
+
+
+/// Wireworld, a four-state automata which simulates electrons flowing along conductors.
+///
+/// Empty cells never change, so the grid never expands beyond the chunks that already contain conductors.
+/// Parses from and displays as the rulestring `Wireworld`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Wireworld;
+
+impl Wireworld {
+  pub const EMPTY: u8 = 0;
+  pub const HEAD: u8 = 1;
+  pub const TAIL: u8 = 2;
+  pub const CONDUCTOR: u8 = 3;
+
+  /// Returns the next state of a cell given its current state and the number of neighbouring electron heads.
+  /// Unknown states are treated as empty.
+  pub const fn next_state(state: u8, heads: u8) -> u8 {
+    match state {
+      Self::HEAD => Self::TAIL,
+      Self::TAIL => Self::CONDUCTOR,
+      Self::CONDUCTOR if heads == 1 || heads == 2 => Self::HEAD,
+      Self::CONDUCTOR => Self::CONDUCTOR,
+      _ => Self::EMPTY
+    }
+  }
+
+  fn next_cell(pos: GlobalPos, state: impl Fn(GlobalPos) -> u8) -> u8 {
+    match state(pos) {
+      Self::CONDUCTOR => {
+        let heads = NEIGHBOURS.iter()
+          .filter(|offset| state([pos[0] + offset[0], pos[1] + offset[1]]) == Self::HEAD)
+          .count();
+        Self::next_state(Self::CONDUCTOR, heads as u8)
+      },
+      current => Self::next_state(current, 0)
+    }
+  }
+}
+
+impl FromStr for Wireworld {
+  type Err = RuleParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.trim().eq_ignore_ascii_case("wireworld") {
+      Ok(Wireworld)
+    } else {
+      Err(RuleParseError::UnknownRule)
+    }
+  }
+}
+
+impl fmt::Display for Wireworld {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("Wireworld")
+  }
+}
+
+impl<const S: usize, H> Automata<ExGridSparse<u8, S, H>> for Wireworld
+where H: BuildHasher {
+  type Expansion = Expansion4;
+
+  /// Empty cells never change, so chunks never need to expand.
+  fn expansion(&mut self, _: &ChunkSparse<u8, S>) -> Expansion4 {
+    Expansion4::default()
+  }
+
+  fn contraction(&mut self, chunk: &ChunkSparse<u8, S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGridSparse<u8, S, H>) -> Option<u8> {
+    let state = Self::next_cell(pos, |pos| grid.get(pos).copied().unwrap_or(Self::EMPTY));
+    (state != Self::EMPTY).then_some(state)
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut ChunkSparse<u8, S>, neighbourhood: &ChunkNeighbourhood<'_, ChunkSparse<u8, S>>, _: &ExGridSparse<u8, S, H>) -> bool {
+    for (local, value) in chunk.as_chunk_mut().cells_mut() {
+      let pos = local.map(|v| v as i64);
+      let state = Self::next_cell(pos, |pos| neighbourhood.get_cell(pos).copied().unwrap_or(Self::EMPTY));
+      *value = (state != Self::EMPTY).then_some(state);
+    };
+
+    true
+  }
+}
+
+impl<const S: usize, H> Automata<ExGrid<u8, S, H>> for Wireworld
+where H: BuildHasher {
+  type Expansion = Expansion4;
+
+  /// Empty cells never change, so chunks never need to expand.
+  fn expansion(&mut self, _: &Chunk<u8, S>) -> Expansion4 {
+    Expansion4::default()
+  }
+
+  fn contraction(&mut self, chunk: &Chunk<u8, S>) -> bool {
+    contract_vacant(chunk)
+  }
+
+  fn simulate(&mut self, pos: GlobalPos, grid: &ExGrid<u8, S, H>) -> u8 {
+    Self::next_cell(pos, |pos| grid.get(pos).copied().unwrap_or(Self::EMPTY))
+  }
+
+  fn simulate_chunk(&mut self, _: ChunkPos, chunk: &mut Chunk<u8, S>, neighbourhood: &ChunkNeighbourhood<'_, Chunk<u8, S>>, _: &ExGrid<u8, S, H>) -> bool {
+    for (local, value) in chunk.cells_mut() {
+      let pos = local.map(|v| v as i64);
+      *value = Self::next_cell(pos, |pos| neighbourhood.get_cell(pos).copied().unwrap_or(Self::EMPTY));
+    };
+
+    true
+  }
+}
@@ -10,9 +10,9 @@ pub use self::iter_par::*;
 #[cfg(feature = "serde")]
 use self::nested_array::NestedArray;
 use crate::LocalPos;
-use crate::vector::{Lerp, Vector2};
+use crate::filter::{Bilinear, Border, Filter};
+use crate::vector::{Lerp, SampleFloat, Vector2};
 
-use num_traits::AsPrimitive;
 #[cfg(feature = "multi-thread")]
 use rayon::iter::{
   IntoParallelIterator,
@@ -69,12 +69,32 @@ impl<T, const S: usize> ChunkSparse<T, S> {
     &self[pos.into()]
   }
 
-  pub fn sample(&self, pos: impl Into<[f32; 2]>) -> Option<T>
-  where T: Lerp<Output = T> + Clone {
-    let pos = Vector2::from_array(pos.into());
-    Chunk::<T, S>::assert_bounds_f(pos);
-    try_sample_2d(pos, |pos: Vector2<usize>| {
-      self[pos].as_ref().cloned()
+  /// Samples a value from the chunk, linearly interpolating the result value.
+  /// Will return `None` if any of the relevant nearby cells are empty.
+  pub fn sample<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> Option<T>
+  where T: Lerp<F, Output = T> + Clone {
+    self.sample_with(pos, Bilinear)
+  }
+
+  /// Samples a value from the chunk using the given filter,
+  /// reading the nearest cell on the edge of the chunk for any cells outside of it.
+  /// Will return `None` if any of the relevant nearby cells are empty.
+  pub fn sample_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> Option<T>
+  where T: Clone {
+    let pos = pos.into();
+    Chunk::<T, S>::assert_bounds_f(Vector2::from_array(pos));
+    self.sample_with_border(pos, filter, Border::Clamp)
+  }
+
+  /// Samples a value from the chunk using the given filter,
+  /// reading any cells outside of the chunk according to `border`.
+  /// Unlike the other sampling methods, `pos` may lie anywhere.
+  /// Will return `None` if any of the relevant nearby cells are empty.
+  pub fn sample_with_border<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>, border: Border<T>) -> Option<T>
+  where T: Clone {
+    filter.sample(pos.into(), |pos| match border.resolve::<S>(pos) {
+      Some(local) => self[local].clone(),
+      None => border.constant().cloned()
     })
   }
 
@@ -103,6 +123,7 @@ impl<T, const S: usize> ChunkSparse<T, S> {
     self.inner.horizontal_slice(y)
   }
 
+  #[cfg_attr(not(feature = "automata"), allow(dead_code))]
   pub(crate) fn horizontal_slice_iter(&self, y: usize) -> impl Iterator<Item = &Option<T>> {
     self.inner.horizontal_slice_iter(y)
   }
@@ -112,27 +133,28 @@ impl<T, const S: usize> ChunkSparse<T, S> {
     self.inner.vertical_slice(x)
   }
 
+  #[cfg_attr(not(feature = "automata"), allow(dead_code))]
   pub(crate) fn vertical_slice_iter(&self, x: usize) -> impl Iterator<Item = &Option<T>> {
     self.inner.vertical_slice_iter(x)
   }
 
   #[inline]
-  pub fn iter(&self) -> ChunkSparseIter<T, S> {
+  pub fn iter(&self) -> ChunkSparseIter<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn iter_mut(&mut self) -> ChunkSparseIterMut<T, S> {
+  pub fn iter_mut(&mut self) -> ChunkSparseIterMut<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn cells(&self) -> ChunkSparseCells<T, S> {
+  pub fn cells(&self) -> ChunkSparseCells<'_, T, S> {
     ChunkSparseCells::new(self)
   }
 
   #[inline]
-  pub fn cells_mut(&mut self) -> ChunkSparseCellsMut<T, S> {
+  pub fn cells_mut(&mut self) -> ChunkSparseCellsMut<'_, T, S> {
     ChunkSparseCellsMut::new(self)
   }
 
@@ -253,7 +275,7 @@ impl<'data, T: Sync + 'data, const S: usize> IntoParallelRefIterator<'data> for
 
   #[inline]
   fn par_iter(&'data self) -> Self::Iter {
-    ChunkSparseIterPar::new(&self)
+    ChunkSparseIterPar::new(self)
   }
 }
 
@@ -328,13 +350,30 @@ impl<T, const S: usize> Chunk<T, S> {
     &self[pos.into()]
   }
 
-  pub fn sample(&self, pos: impl Into<[f32; 2]>) -> T
-  where T: Lerp<Output = T> + Clone {
-    let pos = Vector2::from_array(pos.into());
-    Self::assert_bounds_f(pos);
-    sample_2d(pos, |pos: Vector2<usize>| {
-      self[pos].clone()
-    })
+  /// Samples a value from the chunk, linearly interpolating the result value.
+  pub fn sample<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> T
+  where T: Lerp<F, Output = T> + Clone {
+    self.sample_with(pos, Bilinear)
+  }
+
+  /// Samples a value from the chunk using the given filter,
+  /// reading the nearest cell on the edge of the chunk for any cells outside of it.
+  pub fn sample_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> T
+  where T: Clone {
+    let pos = pos.into();
+    Self::assert_bounds_f(Vector2::from_array(pos));
+    self.sample_with_border(pos, filter, Border::Clamp)
+  }
+
+  /// Samples a value from the chunk using the given filter,
+  /// reading any cells outside of the chunk according to `border`.
+  /// Unlike the other sampling methods, `pos` may lie anywhere.
+  pub fn sample_with_border<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>, border: Border<T>) -> T
+  where T: Clone {
+    filter.sample(pos.into(), |pos| match border.resolve::<S>(pos) {
+      Some(local) => Some(self[local].clone()),
+      None => border.constant().cloned()
+    }).expect("filter should only fail to sample when a cell is missing")
   }
 
   pub fn to_vec(&self) -> Vec<T> where T: Clone {
@@ -351,11 +390,24 @@ impl<T, const S: usize> Chunk<T, S> {
     &self.inner[y]
   }
 
+  #[cfg_attr(not(feature = "automata"), allow(dead_code))]
   pub(crate) fn horizontal_slice_iter(&self, y: usize) -> impl Iterator<Item = &T> {
     Self::assert_bounds_horizontal(y);
     self.inner[y].iter()
   }
 
+  /// Returns an iterator over the rows of this chunk, starting at row `y`.
+  pub(crate) fn rows_from(&self, y: usize) -> std::slice::Iter<'_, [T; S]> {
+    Self::assert_bounds_horizontal(y);
+    self.inner[y..].iter()
+  }
+
+  /// Returns an iterator over the mutable rows of this chunk, starting at row `y`.
+  pub(crate) fn rows_from_mut(&mut self, y: usize) -> std::slice::IterMut<'_, [T; S]> {
+    Self::assert_bounds_horizontal(y);
+    self.inner[y..].iter_mut()
+  }
+
   pub fn vertical_slice(&self, x: usize) -> [T; S] where T: Clone {
     Self::assert_bounds_vertical(x);
     std::array::from_fn(|y| self.inner[y][x].clone())
@@ -366,28 +418,29 @@ impl<T, const S: usize> Chunk<T, S> {
     std::array::from_fn(|y| &self.inner[y][x])
   }
 
+  #[cfg_attr(not(feature = "automata"), allow(dead_code))]
   pub(crate) fn vertical_slice_iter(&self, x: usize) -> impl Iterator<Item = &T> {
     Self::assert_bounds_vertical(x);
     (0..S).map(move |y| &self.inner[y][x])
   }
 
   #[inline]
-  pub fn iter(&self) -> ChunkIter<T, S> {
+  pub fn iter(&self) -> ChunkIter<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn iter_mut(&mut self) -> ChunkIterMut<T, S> {
+  pub fn iter_mut(&mut self) -> ChunkIterMut<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn cells(&self) -> ChunkCells<T, S> {
+  pub fn cells(&self) -> ChunkCells<'_, T, S> {
     ChunkCells::new(self)
   }
 
   #[inline]
-  pub fn cells_mut(&mut self) -> ChunkCellsMut<T, S> {
+  pub fn cells_mut(&mut self) -> ChunkCellsMut<'_, T, S> {
     ChunkCellsMut::new(self)
   }
 
@@ -396,8 +449,9 @@ impl<T, const S: usize> Chunk<T, S> {
     ChunkIntoCells::new(self)
   }
 
-  fn assert_bounds_f(pos: Vector2<f32>) {
-    let in_bounds = pos.x >= 0.0 && pos.y >= 0.0 && pos.x < S as f32 && pos.y < S as f32;
+  fn assert_bounds_f<F: SampleFloat>(pos: Vector2<F>) {
+    let size = F::from(S).expect("chunk size should be representable as a float");
+    let in_bounds = pos.x >= F::zero() && pos.y >= F::zero() && pos.x < size && pos.y < size;
     assert!(in_bounds, "position out of bound: the size is {S} but the position is {}, {}", pos.x, pos.y);
   }
 
@@ -511,7 +565,7 @@ impl<'data, T: Sync + 'data, const S: usize> IntoParallelRefIterator<'data> for
 
   #[inline]
   fn par_iter(&'data self) -> Self::Iter {
-    ChunkIterPar::new(&self)
+    ChunkIterPar::new(self)
   }
 }
 
@@ -556,6 +610,158 @@ where T: Deserialize<'de> {
   }
 }
 
+/// A chunk of boolean cells, storing one bit per cell.
+/// Each row of the chunk is stored as a single `u64`, where bit `x` of row `y` holds the cell at `[x, y]`.
+/// `S` may not be greater than 64.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkBits<const S: usize> {
+  rows: [u64; S]
+}
+
+impl<const S: usize> ChunkBits<S> {
+  const ASSERT_SIZE: () = assert!(S <= 64, "bit chunks may not be wider than 64 cells");
+
+  /// A mask of the bits in a row which correspond to cells in this chunk.
+  pub const ROW_MASK: u64 = if S >= 64 { u64::MAX } else { (1 << S) - 1 };
+
+  pub const fn new() -> Self {
+    #[allow(clippy::let_unit_value)]
+    let () = Self::ASSERT_SIZE;
+    ChunkBits { rows: [0; S] }
+  }
+
+  pub fn init<F: FnMut(LocalPos) -> bool>(mut f: F) -> Self {
+    Self::from_rows(std::array::from_fn(|y| {
+      (0..S).fold(0, |row, x| row | (f([x, y]) as u64) << x)
+    }))
+  }
+
+  /// Creates a chunk from its rows, ignoring any bits outside of [`ChunkBits::ROW_MASK`].
+  pub fn from_rows(rows: [u64; S]) -> Self {
+    let mut chunk = Self::new();
+    chunk.rows = rows.map(|row| row & Self::ROW_MASK);
+    chunk
+  }
+
+  pub fn get(&self, pos: impl Into<LocalPos>) -> bool {
+    let [x, y] = pos.into();
+    Chunk::<bool, S>::assert_bounds_u(Vector2::new(x, y));
+    self.rows[y] >> x & 1 != 0
+  }
+
+  /// Sets the value of a cell, returning its previous value.
+  pub fn set(&mut self, pos: impl Into<LocalPos>, value: bool) -> bool {
+    let [x, y] = pos.into();
+    let previous = self.get([x, y]);
+    self.rows[y] = self.rows[y] & !(1 << x) | (value as u64) << x;
+    previous
+  }
+
+  /// Gets a row of cells as a single word.
+  pub fn row(&self, y: usize) -> u64 {
+    Chunk::<bool, S>::assert_bounds_horizontal(y);
+    self.rows[y]
+  }
+
+  /// Replaces a row of cells, ignoring any bits outside of [`ChunkBits::ROW_MASK`].
+  pub fn set_row(&mut self, y: usize, row: u64) {
+    Chunk::<bool, S>::assert_bounds_horizontal(y);
+    self.rows[y] = row & Self::ROW_MASK;
+  }
+
+  pub fn rows(&self) -> &[u64; S] {
+    &self.rows
+  }
+
+  /// Returns the number of cells in this chunk that are set.
+  pub fn count_occupied(&self) -> usize {
+    self.rows.iter().map(|row| row.count_ones() as usize).sum()
+  }
+
+  /// Returns true if no cells in this chunk are set.
+  pub fn is_all_vacant(&self) -> bool {
+    self.rows.iter().all(|&row| row == 0)
+  }
+
+  /// Returns true if every cell in this chunk is set.
+  pub fn is_all_occupied(&self) -> bool {
+    self.rows.iter().all(|&row| row == Self::ROW_MASK)
+  }
+
+  /// Iterates over the positions of every cell in this chunk that is set.
+  #[inline]
+  pub fn cells(&self) -> ChunkBitsCells<S> {
+    ChunkBitsCells::new(self)
+  }
+}
+
+impl<const S: usize> Index<LocalPos> for ChunkBits<S> {
+  type Output = bool;
+
+  #[inline]
+  fn index(&self, pos: LocalPos) -> &bool {
+    if self.get(pos) { &true } else { &false }
+  }
+}
+
+impl<const S: usize> Default for ChunkBits<S> {
+  #[inline]
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const S: usize> From<Chunk<bool, S>> for ChunkBits<S> {
+  fn from(chunk: Chunk<bool, S>) -> Self {
+    ChunkBits::init(|pos| chunk[pos])
+  }
+}
+
+impl<const S: usize> From<ChunkBits<S>> for Chunk<bool, S> {
+  fn from(chunk: ChunkBits<S>) -> Self {
+    Chunk::init(|pos| chunk.get(pos))
+  }
+}
+
+impl<const S: usize> From<ChunkSparse<(), S>> for ChunkBits<S> {
+  fn from(chunk: ChunkSparse<(), S>) -> Self {
+    ChunkBits::init(|pos| chunk[pos].is_some())
+  }
+}
+
+impl<const S: usize> From<ChunkBits<S>> for ChunkSparse<(), S> {
+  fn from(chunk: ChunkBits<S>) -> Self {
+    ChunkSparse::init(|pos| chunk.get(pos).then_some(()))
+  }
+}
+
+impl<const S: usize> IntoIterator for &ChunkBits<S> {
+  type Item = LocalPos;
+  type IntoIter = ChunkBitsCells<S>;
+
+  #[inline]
+  fn into_iter(self) -> Self::IntoIter {
+    ChunkBitsCells::new(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<const L: usize> Serialize for ChunkBits<L> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde_big_array::Array(self.rows).serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const L: usize> Deserialize<'de> for ChunkBits<L> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    serde_big_array::Array::deserialize(deserializer).map(|rows| ChunkBits::from_rows(rows.0))
+  }
+}
+
+
+
 // This is necessary due to the array primitive's `Default` impl not actually being generic across all `N`.
 fn new_inner<T, F: FnMut(LocalPos) -> T, const N: usize>(mut f: F) -> [[T; N]; N] {
   std::array::from_fn(|y| {
@@ -571,7 +777,7 @@ fn from_nested_array<T, const N: usize>(array: [[T; N]; N]) -> Box<[T]> {
   unsafe {
     // Convert the box into a pointer, then a wide pointer, then a wide box-pointer
     let array_ptr = Box::into_raw(array) as *mut T;
-    let ptr = std::slice::from_raw_parts_mut(array_ptr, len) as *mut [T];
+    let ptr = std::ptr::slice_from_raw_parts_mut(array_ptr, len);
     Box::from_raw(ptr)
   }
 }
@@ -591,56 +797,3 @@ fn from_nested_array_mut<T, const N: usize>(array: &mut [[T; N]; N]) -> &mut [T]
     std::slice::from_raw_parts_mut(ptr, len)
   }
 }
-
-pub(crate) fn lerp_2d<T>(aa: T, ab: T, ba: T, bb: T, factor: Vector2<f32>) -> T
-where T: Lerp<Output = T> {
-  T::lerp(
-    T::lerp(aa, ab, factor.y),
-    T::lerp(ba, bb, factor.y),
-    factor.x
-  )
-}
-
-pub(crate) fn sample_2d<T, D, F>(pos: Vector2<f32>, mut f: F) -> T
-where
-  T: Lerp<Output = T>, F: FnMut(Vector2<D>) -> T,
-  D: AsPrimitive<f32> + Eq, f32: AsPrimitive<D>
-{
-  let min = pos.map(f32::floor).cast::<D>();
-  let max = pos.map(f32::ceil).cast::<D>();
-  if min == max {
-    return f(min);
-  };
-
-  let factor = pos.map(|v| v.rem_euclid(1.0));
-
-  lerp_2d(
-    f(Vector2::new(min.x, min.y)),
-    f(Vector2::new(min.x, max.y)),
-    f(Vector2::new(max.x, min.y)),
-    f(Vector2::new(max.x, max.y)),
-    factor
-  )
-}
-
-pub(crate) fn try_sample_2d<T, D, F>(pos: Vector2<f32>, mut f: F) -> Option<T>
-where
-  T: Lerp<Output = T>, F: FnMut(Vector2<D>) -> Option<T>,
-  D: AsPrimitive<f32> + Eq, f32: AsPrimitive<D>
-{
-  let min = pos.map(f32::floor).cast::<D>();
-  let max = pos.map(f32::ceil).cast::<D>();
-  if min == max {
-    return f(min);
-  };
-
-  let factor = pos.map(|v| v.rem_euclid(1.0));
-
-  Some(lerp_2d(
-    f(Vector2::new(min.x, min.y))?,
-    f(Vector2::new(min.x, max.y))?,
-    f(Vector2::new(max.x, min.y))?,
-    f(Vector2::new(max.x, max.y))?,
-    factor
-  ))
-}
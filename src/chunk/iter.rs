@@ -1,4 +1,4 @@
-use super::{Chunk, ChunkSparse};
+use super::{Chunk, ChunkBits, ChunkSparse};
 use crate::LocalPos;
 
 use std::iter::{Enumerate, FilterMap, Flatten, FusedIterator};
@@ -220,6 +220,55 @@ impl_iterator_known_size!(ChunkIntoCells, <T, S>, (LocalPos, T), S * S);
 
 
 
+/// An iterator over all of the set cells in a bit chunk, in row-major order.
+/// Yields the position of the cell.
+#[derive(Debug, Clone)]
+pub struct ChunkBitsCells<const S: usize> {
+  rows: [u64; S],
+  y: usize
+}
+
+impl<const S: usize> ChunkBitsCells<S> {
+  pub(crate) fn new(chunk: &ChunkBits<S>) -> Self {
+    ChunkBitsCells { rows: *chunk.rows(), y: 0 }
+  }
+}
+
+impl<const S: usize> Iterator for ChunkBitsCells<S> {
+  type Item = LocalPos;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.y < S {
+      let row = &mut self.rows[self.y];
+      if *row != 0 {
+        let x = row.trailing_zeros() as usize;
+        *row &= *row - 1;
+        return Some([x, self.y]);
+      };
+
+      self.y += 1;
+    };
+
+    None
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.len();
+    (len, Some(len))
+  }
+}
+
+impl<const S: usize> ExactSizeIterator for ChunkBitsCells<S> {
+  fn len(&self) -> usize {
+    self.rows[self.y.min(S)..].iter().map(|row| row.count_ones() as usize).sum()
+  }
+}
+
+impl<const S: usize> FusedIterator for ChunkBitsCells<S> {}
+
+
+
 macro_rules! map {
   ($S:expr, $expr:expr) => {
     match $expr {
@@ -0,0 +1,139 @@
+//! Filters which determine how values are sampled from positions between cells.
+
+use crate::{GlobalPos, LocalPos};
+use crate::vector::{Cubic, Lerp, SampleFloat, Vector2};
+
+
+
+/// Describes a method of sampling a value at a position between cells.
+pub trait Filter<T, F: SampleFloat = f32> {
+  /// Samples a value at `pos`, using `get` to read the value of any nearby cells required.
+  /// Returns `None` if `get` returns `None` for any of those cells.
+  fn sample(&self, pos: [F; 2], get: impl FnMut(GlobalPos) -> Option<T>) -> Option<T>;
+}
+
+/// Samples the value of the cell nearest to a position, without interpolating.
+/// Positions exactly halfway between cells round towards positive infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Nearest;
+
+impl<T, F: SampleFloat> Filter<T, F> for Nearest {
+  fn sample(&self, pos: [F; 2], mut get: impl FnMut(GlobalPos) -> Option<T>) -> Option<T> {
+    let half = F::one() / (F::one() + F::one());
+    let pos = Vector2::from_array(pos).map(|v| (v + half).floor());
+    get(pos.cast::<i64>().into_array())
+  }
+}
+
+/// Linearly interpolates between the four cells surrounding a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bilinear;
+
+impl<T, F: SampleFloat> Filter<T, F> for Bilinear
+where T: Lerp<F, Output = T> {
+  fn sample(&self, pos: [F; 2], mut get: impl FnMut(GlobalPos) -> Option<T>) -> Option<T> {
+    let pos = Vector2::from_array(pos);
+    let min = pos.map(F::floor).cast::<i64>();
+    let max = pos.map(F::ceil).cast::<i64>();
+    if min == max {
+      return get(min.into_array());
+    };
+
+    let factor = pos.map(|v| v - v.floor());
+
+    Some(T::lerp(
+      T::lerp(get([min.x, min.y])?, get([min.x, max.y])?, factor.y),
+      T::lerp(get([max.x, min.y])?, get([max.x, max.y])?, factor.y),
+      factor.x
+    ))
+  }
+}
+
+/// Interpolates between the sixteen cells surrounding a position using Catmull-Rom splines.
+/// Along any axis where the position lies exactly on a cell, only that row or column of cells is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bicubic;
+
+impl<T, F: SampleFloat> Filter<T, F> for Bicubic
+where T: Cubic<F, Output = T> {
+  fn sample(&self, pos: [F; 2], mut get: impl FnMut(GlobalPos) -> Option<T>) -> Option<T> {
+    let pos = Vector2::from_array(pos);
+    let base = pos.map(F::floor);
+    let factor = pos - base;
+    let base = base.cast::<i64>();
+
+    let mut row = |y: i64| -> Option<T> {
+      if factor.x == F::zero() {
+        get([base.x, y])
+      } else {
+        Some(T::cubic(
+          get([base.x.saturating_sub(1), y])?,
+          get([base.x, y])?,
+          get([base.x.saturating_add(1), y])?,
+          get([base.x.saturating_add(2), y])?,
+          factor.x
+        ))
+      }
+    };
+
+    if factor.y == F::zero() {
+      row(base.y)
+    } else {
+      Some(T::cubic(
+        row(base.y.saturating_sub(1))?,
+        row(base.y)?,
+        row(base.y.saturating_add(1))?,
+        row(base.y.saturating_add(2))?,
+        factor.y
+      ))
+    }
+  }
+}
+
+/// Describes how cells outside of a chunk are read when sampling from a standalone chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Border<T> {
+  /// Reads the nearest cell on the edge of the chunk.
+  #[default]
+  Clamp,
+  /// Reads cells from the opposite side of the chunk, as if it were tiled infinitely.
+  Wrap,
+  /// Reads cells reflected back into the chunk, as if it were tiled infinitely with every other tile flipped.
+  Mirror,
+  /// Uses a constant value for every cell outside of the chunk.
+  Constant(T)
+}
+
+impl<T> Border<T> {
+  /// Maps a position onto a cell within a chunk of size `S`,
+  /// returning `None` if the border's constant value should be used instead.
+  pub fn resolve<const S: usize>(&self, pos: GlobalPos) -> Option<LocalPos> {
+    let size = i64::try_from(S).expect("chunk size should fit in a global position");
+    let resolve_axis = |p: i64| -> Option<usize> {
+      let p = match self {
+        Border::Clamp => p.clamp(0, size - 1),
+        Border::Wrap => p.rem_euclid(size),
+        Border::Mirror => match p.rem_euclid(size * 2) {
+          p if p < size => p,
+          p => size * 2 - 1 - p
+        },
+        Border::Constant(..) => match p {
+          p if (0..size).contains(&p) => p,
+          _ => return None
+        }
+      };
+
+      Some(p as usize)
+    };
+
+    Some([resolve_axis(pos[0])?, resolve_axis(pos[1])?])
+  }
+
+  /// Returns the constant value used for cells outside of a chunk, if any.
+  pub fn constant(&self) -> Option<&T> {
+    match self {
+      Border::Constant(value) => Some(value),
+      _ => None
+    }
+  }
+}
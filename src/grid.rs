@@ -3,7 +3,8 @@ mod iter;
 pub use self::iter::*;
 use crate::{GlobalPos, ChunkPos, LocalPos};
 use crate::chunk::*;
-use crate::vector::{Lerp, Vector2};
+use crate::filter::{Bilinear, Filter};
+use crate::vector::{Lerp, SampleFloat, Vector2};
 
 #[cfg(feature = "multi-thread")]
 use rayon::collections::hash_map::{
@@ -19,18 +20,20 @@ use rayon::iter::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use std::collections::hash_map::{
-  Entry, HashMap, RandomState,
+  Entry, HashMap, OccupiedEntry, RandomState,
   Iter as HashMapIter,
   IterMut as HashMapIterMut
 };
-use std::hash::BuildHasher;
+use std::fmt;
+use std::hash::{BuildHasher, DefaultHasher, Hash, Hasher};
 use std::mem::replace;
 
 
 
 #[derive(Debug, Clone)]
 pub struct ExGridSparse<T, const S: usize, H = RandomState> {
-  chunks: HashMap<ChunkPos, ChunkSparse<T, S>, H>
+  chunks: HashMap<ChunkPos, ChunkSparse<T, S>, H>,
+  prune_vacant_chunks: bool
 }
 
 impl<T, H, const S: usize> ExGridSparse<T, S, H> {
@@ -55,6 +58,22 @@ impl<T, H, const S: usize> ExGridSparse<T, S, H> {
     self.chunks.retain(|_, chunk| !chunk.is_all_vacant());
   }
 
+  /// Returns true if this grid drops chunks as soon as they become vacant.
+  pub fn prunes_vacant_chunks(&self) -> bool {
+    self.prune_vacant_chunks
+  }
+
+  /// Sets whether this grid should drop chunks as soon as they become vacant through
+  /// methods such as [`ExGridSparse::remove`]. Cells emptied through a mutable reference
+  /// (such as from [`ExGridSparse::get_mut_default`]) are not tracked, use [`ExGridSparse::clean_up`] for those.
+  /// Enabling this will immediately drop any vacant chunks.
+  pub fn set_prune_vacant_chunks(&mut self, prune: bool) {
+    self.prune_vacant_chunks = prune;
+    if prune {
+      self.clean_up();
+    };
+  }
+
   #[doc(hidden)]
   #[deprecated = "use `is_all_vacant` instead"]
   pub fn is_vacant(&self) -> bool {
@@ -80,28 +99,54 @@ impl<T, H, const S: usize> ExGridSparse<T, S, H> {
     self.chunks_bounds().map(map_total_bounds::<S>)
   }
 
+  /// Returns two points `(min, max)` that bound the smallest box containing every occupied cell of this grid.
+  pub fn occupied_bounds(&self) -> Option<(GlobalPos, GlobalPos)> {
+    self.cells().fold(None, |bounds, (pos, _)| match bounds {
+      Some((min, max)) => Some((
+        Vector2::from_array(min).zip_map(pos.into(), i64::min).into_array(),
+        Vector2::from_array(max).zip_map(pos.into(), i64::max).into_array()
+      )),
+      None => Some((pos, pos))
+    })
+  }
+
+  /// Hashes the occupied cells of this grid relative to the minimum corner of their bounds,
+  /// so that translated copies of the same pattern produce the same hash.
+  /// The hash does not depend on the chunk size or hasher of the grid, but may change between builds.
+  pub fn canonical_hash(&self) -> u64 where T: Hash {
+    let min = self.occupied_bounds().map_or([0, 0], |(min, _)| min);
+    let mut cells = self.cells()
+      .map(|(pos, value)| ([pos[0] - min[0], pos[1] - min[1]], value))
+      .collect::<Vec<_>>();
+    cells.sort_unstable_by_key(|&(pos, _)| pos);
+
+    let mut hasher = DefaultHasher::new();
+    cells.hash(&mut hasher);
+    hasher.finish()
+  }
+
   pub fn retain<F>(&mut self, f: F)
   where F: FnMut(&ChunkPos, &mut ChunkSparse<T, S>) -> bool {
     self.chunks.retain(f);
   }
 
   #[inline]
-  pub fn iter(&self) -> ExGridSparseIter<T, S> {
+  pub fn iter(&self) -> ExGridSparseIter<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn iter_mut(&mut self) -> ExGridSparseIterMut<T, S> {
+  pub fn iter_mut(&mut self) -> ExGridSparseIterMut<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn cells(&self) -> ExGridSparseCells<T, S> {
+  pub fn cells(&self) -> ExGridSparseCells<'_, T, S> {
     ExGridSparseCells::new(self)
   }
 
   #[inline]
-  pub fn cells_mut(&mut self) -> ExGridSparseCellsMut<T, S> {
+  pub fn cells_mut(&mut self) -> ExGridSparseCellsMut<'_, T, S> {
     ExGridSparseCellsMut::new(self)
   }
 
@@ -111,18 +156,22 @@ impl<T, H, const S: usize> ExGridSparse<T, S, H> {
   }
 
   #[inline]
-  pub fn chunks(&self) -> HashMapIter<ChunkPos, ChunkSparse<T, S>> {
+  pub fn chunks(&self) -> HashMapIter<'_, ChunkPos, ChunkSparse<T, S>> {
     self.chunks.iter()
   }
 
   #[inline]
-  pub fn chunks_mut(&mut self) -> HashMapIterMut<ChunkPos, ChunkSparse<T, S>> {
+  pub fn chunks_mut(&mut self) -> HashMapIterMut<'_, ChunkPos, ChunkSparse<T, S>> {
     self.chunks.iter_mut()
   }
 
   const NEW_SPARSE_CELLS: FilterSparseCells<T, S> = |(&chunk, i)| Compose::new(chunk, ChunkSparseCells::new(i));
   const NEW_SPARSE_CELLS_MUT: FilterSparseCellsMut<T, S> = |(&chunk, i)| Compose::new(chunk, ChunkSparseCellsMut::new(i));
   const NEW_SPARSE_INTO_CELLS: FilterSparseIntoCells<T, S> = |(chunk, i)| Compose::new(chunk, ChunkSparseIntoCells::new(i));
+  const NEW_SPARSE_WINDOW: FilterSparseWindow<T> = |(pos, v)| (pos, v.and_then(Option::as_ref));
+  const NEW_SPARSE_WINDOW_MUT: FilterSparseWindowMut<T> = |(pos, v)| (pos, v.and_then(Option::as_mut));
+  const NEW_SPARSE_CELLS_IN_RECT: FilterSparseCellsInRect<T> = |(pos, v)| v.as_ref().map(|v| (pos, v));
+  const NEW_SPARSE_CELLS_IN_RECT_MUT: FilterSparseCellsInRectMut<T> = |(pos, v)| v.as_mut().map(|v| (pos, v));
 }
 
 impl<T, H: BuildHasher, const S: usize> ExGridSparse<T, S, H> {
@@ -138,6 +187,20 @@ impl<T, H: BuildHasher, const S: usize> ExGridSparse<T, S, H> {
     self.get_chunk_mut(chunk)?[local].as_mut()
   }
 
+  /// Gets a reference to the value of a cell if it or the chunk it is located in exists,
+  /// returning an error if the position is out of range.
+  pub fn try_get(&self, pos: impl Into<GlobalPos>) -> Result<Option<&T>, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.get_chunk(chunk).and_then(|chunk| chunk[local].as_ref()))
+  }
+
+  /// Gets a mutable reference to the value of a cell if it or the chunk it is located in exists,
+  /// returning an error if the position is out of range.
+  pub fn try_get_mut(&mut self, pos: impl Into<GlobalPos>) -> Result<Option<&mut T>, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.get_chunk_mut(chunk).and_then(|chunk| chunk[local].as_mut()))
+  }
+
   /// Gets a mutable reference to a cell, creating a chunk if necessary.
   pub fn get_mut_default(&mut self, pos: impl Into<GlobalPos>) -> &mut Option<T> {
     let (chunk, local) = decompose::<S>(pos.into());
@@ -148,30 +211,102 @@ impl<T, H: BuildHasher, const S: usize> ExGridSparse<T, S, H> {
     self.get_mut_default(pos).get_or_insert_with(T::default)
   }
 
+  /// Iterates over the occupied cells within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order. Only the chunks which exist within the rectangle are visited.
+  #[inline]
+  pub fn cells_in_rect(&self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridSparseCellsInRect<'_, T, S> {
+    ExGridSparseCellsInRect::new(self, min.into(), max.into())
+  }
+
+  /// Iterates mutably over the occupied cells within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order. Only the chunks which exist within the rectangle are visited.
+  #[inline]
+  pub fn cells_in_rect_mut(&mut self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridSparseCellsInRectMut<'_, T, S> {
+    ExGridSparseCellsInRectMut::new(self, min.into(), max.into())
+  }
+
+  /// Iterates over every position within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order, yielding `None` for empty cells.
+  /// Cells beyond the range of chunk positions are yielded as `None`.
+  ///
+  /// # Panics
+  /// Panics if the rectangle contains more than `usize::MAX` cells.
+  #[inline]
+  pub fn window_in_rect(&self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridSparseWindow<'_, T, S> {
+    ExGridSparseWindow::new(self, min.into(), max.into())
+  }
+
+  /// Iterates mutably over every position within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order, yielding `None` for empty cells.
+  /// Cells beyond the range of chunk positions are yielded as `None`.
+  ///
+  /// # Panics
+  /// Panics if the rectangle contains more than `usize::MAX` cells.
+  #[inline]
+  pub fn window_in_rect_mut(&mut self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridSparseWindowMut<'_, T, S> {
+    ExGridSparseWindowMut::new(self, min.into(), max.into())
+  }
+
   /// Samples a value from the grid, linearly interpolating the result value.
   /// Will return `None` if any of the relevant nearby cells are empty.
-  pub fn try_sample(&self, pos: impl Into<[f32; 2]>) -> Option<T>
-  where T: Lerp<Output = T> + Clone {
-    let pos = Vector2::from_array(pos.into());
-    crate::chunk::try_sample_2d(pos, |pos: Vector2<i64>| {
-      self.get(pos).cloned()
-    })
+  pub fn try_sample<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> Option<T>
+  where T: Lerp<F, Output = T> + Clone {
+    self.try_sample_with(pos, Bilinear)
   }
 
   /// Samples a value from the grid, linearly interpolating the result value.
   /// Uses `T`'s `Default` value whenever a relevant cell is empty.
-  pub fn sample_or_default(&self, pos: impl Into<[f32; 2]>) -> T
-  where T: Lerp<Output = T> + Default + Clone {
-    let pos = Vector2::from_array(pos.into());
-    crate::chunk::sample_2d(pos, |pos: Vector2<i64>| {
-      self.get(pos).cloned().unwrap_or_default()
+  pub fn sample_or_default<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> T
+  where T: Lerp<F, Output = T> + Default + Clone {
+    self.sample_or_default_with(pos, Bilinear)
+  }
+
+  /// Samples a value from the grid using the given filter.
+  /// Will return `None` if any of the relevant nearby cells are empty.
+  /// Cells beyond the range of chunk positions are treated as empty.
+  pub fn try_sample_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> Option<T>
+  where T: Clone {
+    filter.sample(pos.into(), |pos| {
+      self.try_get(pos).ok().flatten().cloned()
     })
   }
 
+  /// Samples a value from the grid using the given filter.
+  /// Uses `T`'s `Default` value whenever a relevant cell is empty.
+  /// Cells beyond the range of chunk positions are treated as empty.
+  pub fn sample_or_default_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> T
+  where T: Default + Clone {
+    filter.sample(pos.into(), |pos| {
+      Some(self.try_get(pos).ok().flatten().cloned().unwrap_or_default())
+    }).expect("filter should only fail to sample when a cell is missing")
+  }
+
   /// Sets the value of a given cell, creating a chunk if necessary,
   /// returning any contained value if present.
   pub fn insert(&mut self, pos: impl Into<GlobalPos>, value: T) -> Option<T> {
-    replace(self.get_mut_default(pos), Some(value))
+    self.get_mut_default(pos).replace(value)
+  }
+
+  /// Sets the value of a given cell, creating a chunk if necessary,
+  /// returning any contained value if present, or an error if the position is out of range.
+  pub fn try_insert(&mut self, pos: impl Into<GlobalPos>, value: T) -> Result<Option<T>, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.get_chunk_default(chunk)[local].replace(value))
+  }
+
+  /// Empties a given cell, returning any contained value if present.
+  /// This never creates a chunk, and will drop the cell's chunk if it
+  /// becomes vacant and this grid prunes vacant chunks.
+  /// Cells beyond the range of chunk positions are never present.
+  pub fn remove(&mut self, pos: impl Into<GlobalPos>) -> Option<T> {
+    let (chunk_pos, local) = try_decompose::<S>(pos.into()).ok()?;
+    let chunk = self.chunks.get_mut(&chunk_pos)?;
+    let value = chunk[local].take()?;
+    if self.prune_vacant_chunks && chunk.is_all_vacant() {
+      self.chunks.remove(&chunk_pos);
+    };
+
+    Some(value)
   }
 
   pub fn contains_chunk(&self, pos: impl Into<ChunkPos>) -> bool {
@@ -190,29 +325,35 @@ impl<T, H: BuildHasher, const S: usize> ExGridSparse<T, S, H> {
     self.get_chunk_entry(pos).or_default()
   }
 
-  pub fn get_chunk_entry(&mut self, pos: impl Into<ChunkPos>) -> Entry<ChunkPos, ChunkSparse<T, S>> {
+  pub fn get_chunk_entry(&mut self, pos: impl Into<ChunkPos>) -> Entry<'_, ChunkPos, ChunkSparse<T, S>> {
     self.chunks.entry(pos.into())
   }
 
   #[cfg(feature = "multi-thread")]
   #[inline]
-  pub fn par_chunks(&self) -> HashMapIterPar<ChunkPos, ChunkSparse<T, S>>
+  pub fn par_chunks(&self) -> HashMapIterPar<'_, ChunkPos, ChunkSparse<T, S>>
   where T: Sync {
     self.chunks.par_iter()
   }
 
   #[cfg(feature = "multi-thread")]
   #[inline]
-  pub fn par_chunks_mut(&mut self) -> HashMapIterMutPar<ChunkPos, ChunkSparse<T, S>>
+  pub fn par_chunks_mut(&mut self) -> HashMapIterMutPar<'_, ChunkPos, ChunkSparse<T, S>>
   where T: Send {
     self.chunks.par_iter_mut()
   }
 
-  pub fn entry(&mut self, pos: impl Into<GlobalPos>) -> ExGridSparseEntry<T, S> {
+  /// Gets the given cell's entry in the grid for in-place manipulation.
+  pub fn entry(&mut self, pos: impl Into<GlobalPos>) -> ExGridSparseEntry<'_, T, S> {
     let (chunk, local) = decompose::<S>(pos.into());
-    ExGridSparseEntry {
-      entry: self.chunks.entry(chunk),
-      pos: local
+    let prune = self.prune_vacant_chunks;
+    match self.chunks.entry(chunk) {
+      Entry::Occupied(entry) if entry.get()[local].is_some() => {
+        ExGridSparseEntry::Occupied(ExGridSparseOccupiedEntry { entry, pos: local, prune })
+      },
+      entry => {
+        ExGridSparseEntry::Vacant(ExGridSparseVacantEntry { entry, pos: local })
+      }
     }
   }
 }
@@ -220,7 +361,7 @@ impl<T, H: BuildHasher, const S: usize> ExGridSparse<T, S, H> {
 impl<T, H: Default, const S: usize> Default for ExGridSparse<T, S, H> {
   #[inline]
   fn default() -> Self {
-    ExGridSparse { chunks: HashMap::default() }
+    ExGridSparse { chunks: HashMap::default(), prune_vacant_chunks: false }
   }
 }
 
@@ -262,6 +403,29 @@ impl<T, H, const S: usize> IntoIterator for ExGridSparse<T, S, H> {
   }
 }
 
+impl<T, H: BuildHasher, const S: usize> Extend<(GlobalPos, T)> for ExGridSparse<T, S, H> {
+  /// Inserts every cell from the iterator, looking up each chunk only once
+  /// for every run of consecutive cells located in the same chunk.
+  fn extend<I: IntoIterator<Item = (GlobalPos, T)>>(&mut self, iter: I) {
+    let mut iter = iter.into_iter().map(decompose_cell::<T, S>).peekable();
+    while let Some((chunk_pos, local, value)) = iter.next() {
+      let chunk = self.get_chunk_default(chunk_pos);
+      chunk[local] = Some(value);
+      while let Some((_, local, value)) = iter.next_if(|&(c, _, _)| c == chunk_pos) {
+        chunk[local] = Some(value);
+      };
+    };
+  }
+}
+
+impl<T, H: BuildHasher + Default, const S: usize> FromIterator<(GlobalPos, T)> for ExGridSparse<T, S, H> {
+  fn from_iter<I: IntoIterator<Item = (GlobalPos, T)>>(iter: I) -> Self {
+    let mut grid = ExGridSparse::default();
+    grid.extend(iter);
+    grid
+  }
+}
+
 #[cfg(feature = "serde")]
 impl<T, const L: usize, H> Serialize for ExGridSparse<T, L, H>
 where T: Serialize {
@@ -276,27 +440,129 @@ impl<'de, T, const L: usize, H> Deserialize<'de> for ExGridSparse<T, L, H>
 where T: Deserialize<'de>, H: BuildHasher + Default {
   #[inline]
   fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-    HashMap::deserialize(deserializer).map(|chunks| ExGridSparse { chunks })
+    HashMap::deserialize(deserializer).map(|chunks| ExGridSparse { chunks, prune_vacant_chunks: false })
   }
 }
 
+/// A view into a single cell of an [`ExGridSparse`], which may either be vacant or occupied.
 #[derive(Debug)]
-pub struct ExGridSparseEntry<'a, T, const S: usize> {
-  entry: Entry<'a, ChunkPos, ChunkSparse<T, S>>,
-  pos: LocalPos
+pub enum ExGridSparseEntry<'a, T, const S: usize> {
+  Occupied(ExGridSparseOccupiedEntry<'a, T, S>),
+  Vacant(ExGridSparseVacantEntry<'a, T, S>)
 }
 
 impl<'a, T, const S: usize> ExGridSparseEntry<'a, T, S> {
-  pub fn or_insert(self, default: ChunkSparse<T, S>) -> &'a mut Option<T> {
-    &mut self.entry.or_insert(default)[self.pos]
+  /// Returns the position of this entry's cell.
+  pub fn key(&self) -> GlobalPos {
+    match self {
+      ExGridSparseEntry::Occupied(entry) => entry.key(),
+      ExGridSparseEntry::Vacant(entry) => entry.key()
+    }
   }
 
-  pub fn or_insert_with<F: FnOnce() -> ChunkSparse<T, S>>(self, default: F) -> &'a mut Option<T> {
-    &mut self.entry.or_insert_with(default)[self.pos]
+  /// Provides in-place mutable access to an occupied cell before any potential inserts.
+  pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+    if let ExGridSparseEntry::Occupied(entry) = &mut self {
+      f(entry.get_mut());
+    };
+
+    self
   }
 
-  pub fn or_insert_with_key<F: FnOnce(ChunkPos) -> ChunkSparse<T, S>>(self, default: F) -> &'a mut Option<T> {
-    &mut self.entry.or_insert_with_key(move |&k| default(k))[self.pos]
+  /// Inserts `default` into the cell if it is vacant, creating a chunk if necessary.
+  pub fn or_insert(self, default: T) -> &'a mut T {
+    self.or_insert_with(move || default)
+  }
+
+  /// Inserts the result of `default` into the cell if it is vacant, creating a chunk if necessary.
+  pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+    self.or_insert_with_key(move |_| default())
+  }
+
+  /// Inserts the result of `default` into the cell if it is vacant, creating a chunk if necessary.
+  /// The position of the cell is passed to `default`.
+  pub fn or_insert_with_key<F: FnOnce(GlobalPos) -> T>(self, default: F) -> &'a mut T {
+    match self {
+      ExGridSparseEntry::Occupied(entry) => entry.into_mut(),
+      ExGridSparseEntry::Vacant(entry) => {
+        let value = default(entry.key());
+        entry.insert(value)
+      }
+    }
+  }
+
+  /// Inserts `T`'s `Default` value into the cell if it is vacant, creating a chunk if necessary.
+  pub fn or_default(self) -> &'a mut T where T: Default {
+    self.or_insert_with(T::default)
+  }
+}
+
+/// A view into an occupied cell of an [`ExGridSparse`].
+#[derive(Debug)]
+pub struct ExGridSparseOccupiedEntry<'a, T, const S: usize> {
+  entry: OccupiedEntry<'a, ChunkPos, ChunkSparse<T, S>>,
+  pos: LocalPos,
+  prune: bool
+}
+
+impl<'a, T, const S: usize> ExGridSparseOccupiedEntry<'a, T, S> {
+  /// Returns the position of this entry's cell.
+  pub fn key(&self) -> GlobalPos {
+    compose::<S>(*self.entry.key(), self.pos)
+  }
+
+  pub fn get(&self) -> &T {
+    self.entry.get()[self.pos].as_ref().expect("occupied entry is vacant")
+  }
+
+  pub fn get_mut(&mut self) -> &mut T {
+    self.entry.get_mut()[self.pos].as_mut().expect("occupied entry is vacant")
+  }
+
+  /// Converts this entry into a mutable reference to the cell's value, with the lifetime of the grid.
+  pub fn into_mut(self) -> &'a mut T {
+    self.entry.into_mut()[self.pos].as_mut().expect("occupied entry is vacant")
+  }
+
+  /// Sets the value of the cell, returning the previous value.
+  pub fn insert(&mut self, value: T) -> T {
+    replace(self.get_mut(), value)
+  }
+
+  /// Empties the cell, returning its value.
+  pub fn remove(self) -> T {
+    self.remove_entry().1
+  }
+
+  /// Empties the cell, returning its position and value.
+  /// The cell's chunk is dropped if it becomes vacant and the grid prunes vacant chunks.
+  pub fn remove_entry(mut self) -> (GlobalPos, T) {
+    let key = self.key();
+    let value = self.entry.get_mut()[self.pos].take().expect("occupied entry is vacant");
+    if self.prune && self.entry.get().is_all_vacant() {
+      self.entry.remove();
+    };
+
+    (key, value)
+  }
+}
+
+/// A view into a vacant cell of an [`ExGridSparse`], whose chunk may or may not exist.
+#[derive(Debug)]
+pub struct ExGridSparseVacantEntry<'a, T, const S: usize> {
+  entry: Entry<'a, ChunkPos, ChunkSparse<T, S>>,
+  pos: LocalPos
+}
+
+impl<'a, T, const S: usize> ExGridSparseVacantEntry<'a, T, S> {
+  /// Returns the position of this entry's cell.
+  pub fn key(&self) -> GlobalPos {
+    compose::<S>(*self.entry.key(), self.pos)
+  }
+
+  /// Sets the value of the cell, creating a chunk if necessary.
+  pub fn insert(self, value: T) -> &'a mut T {
+    self.entry.or_default()[self.pos].insert(value)
   }
 }
 
@@ -341,22 +607,22 @@ impl<T, H, const S: usize> ExGrid<T, S, H> {
   }
 
   #[inline]
-  pub fn iter(&self) -> ExGridIter<T, S> {
+  pub fn iter(&self) -> ExGridIter<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn iter_mut(&mut self) -> ExGridIterMut<T, S> {
+  pub fn iter_mut(&mut self) -> ExGridIterMut<'_, T, S> {
     self.into_iter()
   }
 
   #[inline]
-  pub fn cells(&self) -> ExGridCells<T, S> {
+  pub fn cells(&self) -> ExGridCells<'_, T, S> {
     ExGridCells::new(self)
   }
 
   #[inline]
-  pub fn cells_mut(&mut self) -> ExGridCellsMut<T, S> {
+  pub fn cells_mut(&mut self) -> ExGridCellsMut<'_, T, S> {
     ExGridCellsMut::new(self)
   }
 
@@ -366,12 +632,12 @@ impl<T, H, const S: usize> ExGrid<T, S, H> {
   }
 
   #[inline]
-  pub fn chunks(&self) -> HashMapIter<ChunkPos, Chunk<T, S>> {
+  pub fn chunks(&self) -> HashMapIter<'_, ChunkPos, Chunk<T, S>> {
     self.chunks.iter()
   }
 
   #[inline]
-  pub fn chunks_mut(&mut self) -> HashMapIterMut<ChunkPos, Chunk<T, S>> {
+  pub fn chunks_mut(&mut self) -> HashMapIterMut<'_, ChunkPos, Chunk<T, S>> {
     self.chunks.iter_mut()
   }
 
@@ -393,6 +659,20 @@ impl<T, H: BuildHasher, const S: usize> ExGrid<T, S, H> {
     self.chunks.get_mut(&chunk).map(|c| &mut c[local])
   }
 
+  /// Gets a reference to the value of a cell if the chunk it is located in exists,
+  /// returning an error if the position is out of range.
+  pub fn try_get(&self, pos: impl Into<GlobalPos>) -> Result<Option<&T>, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.chunks.get(&chunk).map(|c| &c[local]))
+  }
+
+  /// Gets a mutable reference to the value of a cell if the chunk it is located in exists,
+  /// returning an error if the position is out of range.
+  pub fn try_get_mut(&mut self, pos: impl Into<GlobalPos>) -> Result<Option<&mut T>, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.chunks.get_mut(&chunk).map(|c| &mut c[local]))
+  }
+
   /// Gets a mutable reference to the value of a cell, creating a chunk if necessary.
   pub fn get_mut_default(&mut self, pos: impl Into<GlobalPos>) -> &mut T
   where T: Default {
@@ -400,37 +680,96 @@ impl<T, H: BuildHasher, const S: usize> ExGrid<T, S, H> {
     &mut self.get_chunk_default(chunk)[local]
   }
 
+  /// Iterates over the cells within the rectangle between `min` and `max` (inclusive)
+  /// whose chunks exist, in row-major order. Only the chunks which exist within the rectangle are visited.
+  #[inline]
+  pub fn cells_in_rect(&self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridCellsInRect<'_, T, S> {
+    ExGridCellsInRect::new(self, min.into(), max.into())
+  }
+
+  /// Iterates mutably over the cells within the rectangle between `min` and `max` (inclusive)
+  /// whose chunks exist, in row-major order. Only the chunks which exist within the rectangle are visited.
+  #[inline]
+  pub fn cells_in_rect_mut(&mut self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridCellsInRectMut<'_, T, S> {
+    ExGridCellsInRectMut::new(self, min.into(), max.into())
+  }
+
+  /// Iterates over every position within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order, yielding `None` for cells whose chunks do not exist.
+  /// Cells beyond the range of chunk positions are yielded as `None`.
+  ///
+  /// # Panics
+  /// Panics if the rectangle contains more than `usize::MAX` cells.
+  #[inline]
+  pub fn window_in_rect(&self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridWindow<'_, T, S> {
+    ExGridWindow::new(self, min.into(), max.into())
+  }
+
+  /// Iterates mutably over every position within the rectangle between `min` and `max` (inclusive),
+  /// in row-major order, yielding `None` for cells whose chunks do not exist.
+  /// Cells beyond the range of chunk positions are yielded as `None`.
+  ///
+  /// # Panics
+  /// Panics if the rectangle contains more than `usize::MAX` cells.
+  #[inline]
+  pub fn window_in_rect_mut(&mut self, min: impl Into<GlobalPos>, max: impl Into<GlobalPos>) -> ExGridWindowMut<'_, T, S> {
+    ExGridWindowMut::new(self, min.into(), max.into())
+  }
+
   /// Samples a value from the grid, linearly interpolating the result value.
   /// Will return `None` if any of the relevant nearby cells are empty.
-  pub fn try_sample(&self, pos: impl Into<[f32; 2]>) -> Option<T>
-  where T: Lerp<Output = T> + Clone {
-    let pos = Vector2::from_array(pos.into());
-    crate::chunk::try_sample_2d(pos, |pos: Vector2<i64>| {
-      self.get(pos).cloned()
-    })
+  pub fn try_sample<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> Option<T>
+  where T: Lerp<F, Output = T> + Clone {
+    self.try_sample_with(pos, Bilinear)
   }
 
   /// Samples a value from the grid, linearly interpolating the result value.
   /// Uses `T`'s `Default` value whenever a relevant cell is empty.
-  pub fn sample_or_default(&self, pos: impl Into<[f32; 2]>) -> T
-  where T: Lerp<Output = T> + Default + Clone {
-    let pos = Vector2::from_array(pos.into());
-    crate::chunk::sample_2d(pos, |pos: Vector2<i64>| {
-      self.get(pos).cloned().unwrap_or_default()
-    })
+  pub fn sample_or_default<F: SampleFloat>(&self, pos: impl Into<[F; 2]>) -> T
+  where T: Lerp<F, Output = T> + Default + Clone {
+    self.sample_or_default_with(pos, Bilinear)
   }
 
   /// Samples a value from the grid, linearly interpolating the result value.
   /// Uses `T`'s `Default` value whenever a relevant cell is empty,
   /// inserting that value into the empty cell.
-  pub fn sample_insert_default(&mut self, pos: impl Into<[f32; 2]>) -> T
-  where T: Lerp<Output = T> + Default + Clone {
-    let pos = Vector2::from_array(pos.into());
-    crate::chunk::sample_2d(pos, |pos: Vector2<i64>| {
-      self.get_mut_default(pos).clone()
+  pub fn sample_insert_default<F: SampleFloat>(&mut self, pos: impl Into<[F; 2]>) -> T
+  where T: Lerp<F, Output = T> + Default + Clone {
+    self.sample_insert_default_with(pos, Bilinear)
+  }
+
+  /// Samples a value from the grid using the given filter.
+  /// Will return `None` if any of the relevant nearby cells are empty.
+  /// Cells beyond the range of chunk positions are treated as empty.
+  pub fn try_sample_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> Option<T>
+  where T: Clone {
+    filter.sample(pos.into(), |pos| {
+      self.try_get(pos).ok().flatten().cloned()
     })
   }
 
+  /// Samples a value from the grid using the given filter.
+  /// Uses `T`'s `Default` value whenever a relevant cell is empty.
+  /// Cells beyond the range of chunk positions are treated as empty.
+  pub fn sample_or_default_with<F: SampleFloat>(&self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> T
+  where T: Default + Clone {
+    filter.sample(pos.into(), |pos| {
+      Some(self.try_get(pos).ok().flatten().cloned().unwrap_or_default())
+    }).expect("filter should only fail to sample when a cell is missing")
+  }
+
+  /// Samples a value from the grid using the given filter.
+  /// Uses `T`'s `Default` value whenever a relevant cell is empty,
+  /// inserting that value into the empty cell.
+  /// Cells beyond the range of chunk positions are treated as empty, and are never inserted.
+  pub fn sample_insert_default_with<F: SampleFloat>(&mut self, pos: impl Into<[F; 2]>, filter: impl Filter<T, F>) -> T
+  where T: Default + Clone {
+    filter.sample(pos.into(), |pos| match try_decompose::<S>(pos) {
+      Ok((chunk, local)) => Some(self.get_chunk_default(chunk)[local].clone()),
+      Err(_) => Some(T::default())
+    }).expect("filter should only fail to sample when a cell is missing")
+  }
+
   /// Sets the value of a given cell, creating a chunk if necessary,
   /// returning the previously contained value.
   pub fn insert_default(&mut self, pos: impl Into<GlobalPos>, value: T) -> T
@@ -438,6 +777,14 @@ impl<T, H: BuildHasher, const S: usize> ExGrid<T, S, H> {
     replace(self.get_mut_default(pos), value)
   }
 
+  /// Sets the value of a given cell, creating a chunk if necessary,
+  /// returning the previously contained value, or an error if the position is out of range.
+  pub fn try_insert_default(&mut self, pos: impl Into<GlobalPos>, value: T) -> Result<T, PositionError>
+  where T: Default {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(replace(&mut self.get_chunk_default(chunk)[local], value))
+  }
+
   pub fn contains_chunk(&self, pos: impl Into<ChunkPos>) -> bool {
     self.chunks.contains_key(&pos.into())
   }
@@ -455,25 +802,26 @@ impl<T, H: BuildHasher, const S: usize> ExGrid<T, S, H> {
     self.get_chunk_entry(pos).or_default()
   }
 
-  pub fn get_chunk_entry(&mut self, pos: impl Into<ChunkPos>) -> Entry<ChunkPos, Chunk<T, S>> {
+  pub fn get_chunk_entry(&mut self, pos: impl Into<ChunkPos>) -> Entry<'_, ChunkPos, Chunk<T, S>> {
     self.chunks.entry(pos.into())
   }
 
   #[cfg(feature = "multi-thread")]
   #[inline]
-  pub fn par_chunks(&self) -> HashMapIterPar<ChunkPos, Chunk<T, S>>
+  pub fn par_chunks(&self) -> HashMapIterPar<'_, ChunkPos, Chunk<T, S>>
   where T: Sync {
     self.chunks.par_iter()
   }
 
   #[cfg(feature = "multi-thread")]
   #[inline]
-  pub fn par_chunks_mut(&mut self) -> HashMapIterMutPar<ChunkPos, Chunk<T, S>>
+  pub fn par_chunks_mut(&mut self) -> HashMapIterMutPar<'_, ChunkPos, Chunk<T, S>>
   where T: Send {
     self.chunks.par_iter_mut()
   }
 
-  pub fn entry(&mut self, pos: impl Into<GlobalPos>) -> ExGridEntry<T, S> {
+  /// Gets the given cell's entry in the grid for in-place manipulation.
+  pub fn entry(&mut self, pos: impl Into<GlobalPos>) -> ExGridEntry<'_, T, S> {
     let (chunk, local) = decompose::<S>(pos.into());
     ExGridEntry {
       entry: self.chunks.entry(chunk),
@@ -527,6 +875,29 @@ impl<T, H, const S: usize> IntoIterator for ExGrid<T, S, H> {
   }
 }
 
+impl<T: Default, H: BuildHasher, const S: usize> Extend<(GlobalPos, T)> for ExGrid<T, S, H> {
+  /// Sets every cell from the iterator, creating chunks if necessary, looking up each chunk
+  /// only once for every run of consecutive cells located in the same chunk.
+  fn extend<I: IntoIterator<Item = (GlobalPos, T)>>(&mut self, iter: I) {
+    let mut iter = iter.into_iter().map(decompose_cell::<T, S>).peekable();
+    while let Some((chunk_pos, local, value)) = iter.next() {
+      let chunk = self.get_chunk_default(chunk_pos);
+      chunk[local] = value;
+      while let Some((_, local, value)) = iter.next_if(|&(c, _, _)| c == chunk_pos) {
+        chunk[local] = value;
+      };
+    };
+  }
+}
+
+impl<T: Default, H: BuildHasher + Default, const S: usize> FromIterator<(GlobalPos, T)> for ExGrid<T, S, H> {
+  fn from_iter<I: IntoIterator<Item = (GlobalPos, T)>>(iter: I) -> Self {
+    let mut grid = ExGrid::default();
+    grid.extend(iter);
+    grid
+  }
+}
+
 #[cfg(feature = "serde")]
 impl<T, const L: usize, H> Serialize for ExGrid<T, L, H>
 where T: Serialize {
@@ -552,6 +923,23 @@ pub struct ExGridEntry<'a, T, const S: usize> {
 }
 
 impl<'a, T, const S: usize> ExGridEntry<'a, T, S> {
+  /// Returns the position of this entry's cell.
+  pub fn key(&self) -> GlobalPos {
+    compose::<S>(*self.entry.key(), self.pos)
+  }
+
+  /// Provides in-place mutable access to the cell if its chunk exists, before any potential inserts.
+  pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+    let pos = self.pos;
+    let entry = self.entry.and_modify(move |chunk| f(&mut chunk[pos]));
+    ExGridEntry { entry, pos }
+  }
+
+  /// Inserts a chunk filled with `T`'s `Default` value if the cell's chunk does not exist.
+  pub fn or_default(self) -> &'a mut T where T: Default {
+    &mut self.entry.or_default()[self.pos]
+  }
+
   pub fn or_insert(self, default: Chunk<T, S>) -> &'a mut T {
     &mut self.entry.or_insert(default)[self.pos]
   }
@@ -567,29 +955,306 @@ impl<'a, T, const S: usize> ExGridEntry<'a, T, S> {
 
 
 
+
+/// A grid of boolean cells built from [`ChunkBits`], storing one bit per cell.
+/// Cells in chunks that do not exist are considered unset.
+#[derive(Debug, Clone)]
+pub struct ExGridBits<const S: usize, H = RandomState> {
+  chunks: HashMap<ChunkPos, ChunkBits<S>, H>
+}
+
+impl<H, const S: usize> ExGridBits<S, H> {
+  #[inline]
+  pub fn new() -> Self where H: Default {
+    Self::default()
+  }
+
+  pub fn clear(&mut self) {
+    self.chunks.clear();
+  }
+
+  pub fn chunks_count(&self) -> usize {
+    self.chunks.len()
+  }
+
+  /// Returns the number of cells in this grid that are set.
+  pub fn count_occupied(&self) -> usize {
+    self.chunks.values().map(ChunkBits::count_occupied).sum()
+  }
+
+  pub fn clean_up(&mut self) {
+    self.chunks.retain(|_, chunk| !chunk.is_all_vacant());
+  }
+
+  pub fn is_all_vacant(&self) -> bool {
+    self.chunks.values().all(ChunkBits::is_all_vacant)
+  }
+
+  /// Returns two points `(min, max)` that bound a box containing the all chunks in this grid.
+  pub fn chunks_bounds(&self) -> Option<(ChunkPos, ChunkPos)> {
+    chunks_bounds(&self.chunks)
+  }
+
+  /// Returns two points `(min, max)` that bound a box containing all possible cells of this grid.
+  /// This is "naive" because it may overestimate.
+  pub fn naive_bounds(&self) -> Option<(GlobalPos, GlobalPos)> {
+    self.chunks_bounds().map(map_total_bounds::<S>)
+  }
+
+  pub fn retain<F>(&mut self, f: F)
+  where F: FnMut(&ChunkPos, &mut ChunkBits<S>) -> bool {
+    self.chunks.retain(f);
+  }
+
+  /// Iterates over the positions of every cell in this grid that is set.
+  #[inline]
+  pub fn cells(&self) -> ExGridBitsCells<'_, S> {
+    ExGridBitsCells::new(self)
+  }
+
+  #[inline]
+  pub fn chunks(&self) -> HashMapIter<'_, ChunkPos, ChunkBits<S>> {
+    self.chunks.iter()
+  }
+
+  #[inline]
+  pub fn chunks_mut(&mut self) -> HashMapIterMut<'_, ChunkPos, ChunkBits<S>> {
+    self.chunks.iter_mut()
+  }
+}
+
+impl<H: BuildHasher, const S: usize> ExGridBits<S, H> {
+  /// Gets the value of a cell, which is unset if the chunk it is located in does not exist.
+  pub fn get(&self, pos: impl Into<GlobalPos>) -> bool {
+    let (chunk, local) = decompose::<S>(pos.into());
+    self.chunks.get(&chunk).is_some_and(|c| c.get(local))
+  }
+
+  /// Gets the value of a cell, returning an error if the position is out of range.
+  pub fn try_get(&self, pos: impl Into<GlobalPos>) -> Result<bool, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(self.chunks.get(&chunk).is_some_and(|c| c.get(local)))
+  }
+
+  /// Sets the value of a cell, returning its previous value.
+  /// Setting a cell creates its chunk if necessary, unsetting a cell never creates a chunk.
+  pub fn set(&mut self, pos: impl Into<GlobalPos>, value: bool) -> bool {
+    let (chunk, local) = decompose::<S>(pos.into());
+    set_bit(&mut self.chunks, chunk, local, value)
+  }
+
+  /// Sets the value of a cell, returning its previous value,
+  /// or an error if the position is out of range.
+  pub fn try_set(&mut self, pos: impl Into<GlobalPos>, value: bool) -> Result<bool, PositionError> {
+    let (chunk, local) = try_decompose::<S>(pos.into())?;
+    Ok(set_bit(&mut self.chunks, chunk, local, value))
+  }
+
+  pub fn contains_chunk(&self, pos: impl Into<ChunkPos>) -> bool {
+    self.chunks.contains_key(&pos.into())
+  }
+
+  pub fn get_chunk(&self, pos: impl Into<ChunkPos>) -> Option<&ChunkBits<S>> {
+    self.chunks.get(&pos.into())
+  }
+
+  pub fn get_chunk_mut(&mut self, pos: impl Into<ChunkPos>) -> Option<&mut ChunkBits<S>> {
+    self.chunks.get_mut(&pos.into())
+  }
+
+  pub fn get_chunk_default(&mut self, pos: impl Into<ChunkPos>) -> &mut ChunkBits<S> {
+    self.chunks.entry(pos.into()).or_default()
+  }
+
+  pub fn get_chunk_entry(&mut self, pos: impl Into<ChunkPos>) -> Entry<'_, ChunkPos, ChunkBits<S>> {
+    self.chunks.entry(pos.into())
+  }
+}
+
+impl<H: Default, const S: usize> Default for ExGridBits<S, H> {
+  #[inline]
+  fn default() -> Self {
+    ExGridBits { chunks: HashMap::default() }
+  }
+}
+
+impl<H: BuildHasher, const S: usize> Eq for ExGridBits<S, H> {}
+
+impl<H: BuildHasher, const S: usize> PartialEq for ExGridBits<S, H> {
+  fn eq(&self, other: &Self) -> bool {
+    self.chunks == other.chunks
+  }
+}
+
+impl<'a, H, const S: usize> IntoIterator for &'a ExGridBits<S, H> {
+  type Item = GlobalPos;
+  type IntoIter = ExGridBitsCells<'a, S>;
+
+  #[inline]
+  fn into_iter(self) -> Self::IntoIter {
+    ExGridBitsCells::new(self)
+  }
+}
+
+impl<H: BuildHasher, const S: usize> Extend<GlobalPos> for ExGridBits<S, H> {
+  /// Sets every cell from the iterator.
+  fn extend<I: IntoIterator<Item = GlobalPos>>(&mut self, iter: I) {
+    for pos in iter {
+      self.set(pos, true);
+    };
+  }
+}
+
+impl<H: BuildHasher + Default, const S: usize> FromIterator<GlobalPos> for ExGridBits<S, H> {
+  fn from_iter<I: IntoIterator<Item = GlobalPos>>(iter: I) -> Self {
+    let mut grid = ExGridBits::default();
+    grid.extend(iter);
+    grid
+  }
+}
+
+impl<H: BuildHasher + Default, const S: usize> From<ExGridSparse<(), S, H>> for ExGridBits<S, H> {
+  fn from(grid: ExGridSparse<(), S, H>) -> Self {
+    let chunks = grid.chunks.into_iter().map(|(pos, chunk)| (pos, chunk.into())).collect();
+    ExGridBits { chunks }
+  }
+}
+
+impl<H: BuildHasher + Default, const S: usize> From<ExGridBits<S, H>> for ExGridSparse<(), S, H> {
+  fn from(grid: ExGridBits<S, H>) -> Self {
+    let chunks = grid.chunks.into_iter().map(|(pos, chunk)| (pos, chunk.into())).collect();
+    ExGridSparse { chunks, prune_vacant_chunks: false }
+  }
+}
+
+impl<H: BuildHasher + Default, const S: usize> From<ExGrid<bool, S, H>> for ExGridBits<S, H> {
+  fn from(grid: ExGrid<bool, S, H>) -> Self {
+    let chunks = grid.chunks.into_iter().map(|(pos, chunk)| (pos, chunk.into())).collect();
+    ExGridBits { chunks }
+  }
+}
+
+impl<H: BuildHasher + Default, const S: usize> From<ExGridBits<S, H>> for ExGrid<bool, S, H> {
+  fn from(grid: ExGridBits<S, H>) -> Self {
+    let chunks = grid.chunks.into_iter().map(|(pos, chunk)| (pos, chunk.into())).collect();
+    ExGrid { chunks }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<const L: usize, H> Serialize for ExGridBits<L, H> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    HashMap::serialize(&self.chunks, serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const L: usize, H> Deserialize<'de> for ExGridBits<L, H>
+where H: BuildHasher + Default {
+  #[inline]
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    HashMap::deserialize(deserializer).map(|chunks| ExGridBits { chunks })
+  }
+}
+
+
 type FilterSparseCells<T, const S: usize> = for<'r> fn((&'r ChunkPos, &'r ChunkSparse<T, S>)) -> Compose<ChunkSparseCells<'r, T, S>, S>;
 type FilterSparseCellsMut<T, const S: usize> = for<'r> fn((&'r ChunkPos, &'r mut ChunkSparse<T, S>)) -> Compose<ChunkSparseCellsMut<'r, T, S>, S>;
 type FilterSparseIntoCells<T, const S: usize> = fn((ChunkPos, ChunkSparse<T, S>)) -> Compose<ChunkSparseIntoCells<T, S>, S>;
 type FilterCells<T, const S: usize> = for<'r> fn((&'r ChunkPos, &'r Chunk<T, S>)) -> Compose<ChunkCells<'r, T, S>, S>;
 type FilterCellsMut<T, const S: usize> = for<'r> fn((&'r ChunkPos, &'r mut Chunk<T, S>)) -> Compose<ChunkCellsMut<'r, T, S>, S>;
 type FilterIntoCells<T, const S: usize> = fn((ChunkPos, Chunk<T, S>)) -> Compose<ChunkIntoCells<T, S>, S>;
+type FilterSparseWindow<T> = for<'r> fn((GlobalPos, Option<&'r Option<T>>)) -> (GlobalPos, Option<&'r T>);
+type FilterSparseWindowMut<T> = for<'r> fn((GlobalPos, Option<&'r mut Option<T>>)) -> (GlobalPos, Option<&'r mut T>);
+type FilterSparseCellsInRect<T> = for<'r> fn((GlobalPos, &'r Option<T>)) -> Option<(GlobalPos, &'r T)>;
+type FilterSparseCellsInRectMut<T> = for<'r> fn((GlobalPos, &'r mut Option<T>)) -> Option<(GlobalPos, &'r mut T)>;
 
 /// Converts global coordinates to coordinates for a single chunk
 /// and coordinates to a cell in that chunk.
+///
+/// Global positions whose chunk lies outside of the range of [`ChunkPos`] will wrap around to a different chunk.
+/// With the `checked-positions` feature enabled, debug builds panic instead. See [`try_decompose`].
 pub fn decompose<const S: usize>(pos: GlobalPos) -> (ChunkPos, LocalPos) {
   assert!(S > 0, "cannot index into a grid or chunk of size 0");
+  #[cfg(feature = "checked-positions")]
+  debug_assert!(try_decompose::<S>(pos).is_ok(), "{}", PositionError::ChunkOutOfRange(pos));
   let chunk = pos.map(|p| p.div_euclid(S as i64) as i32);
   let local = pos.map(|p| p.rem_euclid(S as i64) as usize);
   (chunk, local)
 }
 
+/// Converts chunk coordinates and coordinates to a cell in that chunk to global coordinates.
+///
+/// Positions which lie outside of the range of [`GlobalPos`] will overflow.
+/// With the `checked-positions` feature enabled, debug builds panic instead. See [`try_compose`].
 pub fn compose<const S: usize>(chunk: ChunkPos, local: LocalPos) -> GlobalPos {
   assert!(S > 0, "cannot index into a grid or chunk of size 0");
+  #[cfg(feature = "checked-positions")]
+  debug_assert!(try_compose::<S>(chunk, local).is_ok(), "{}", PositionError::GlobalOutOfRange(chunk, local));
   let chunk = Vector2::from_array(chunk);
   let local = Vector2::from_array(local);
   Vector2::into_array(chunk.cast::<i64>() * S as i64 + local.cast::<i64>())
 }
 
+/// Converts global coordinates to coordinates for a single chunk and coordinates to a cell in that chunk,
+/// returning an error if the chunk lies outside of the range of [`ChunkPos`].
+pub fn try_decompose<const S: usize>(pos: GlobalPos) -> Result<(ChunkPos, LocalPos), PositionError> {
+  assert!(S > 0, "cannot index into a grid or chunk of size 0");
+  match pos.map(|p| i32::try_from(p.div_euclid(S as i64))) {
+    [Ok(x), Ok(y)] => Ok(([x, y], pos.map(|p| p.rem_euclid(S as i64) as usize))),
+    _ => Err(PositionError::ChunkOutOfRange(pos))
+  }
+}
+
+/// Converts chunk coordinates and coordinates to a cell in that chunk to global coordinates,
+/// returning an error if the local coordinates lie outside of the chunk,
+/// or if the resulting position lies outside of the range of [`GlobalPos`].
+pub fn try_compose<const S: usize>(chunk: ChunkPos, local: LocalPos) -> Result<GlobalPos, PositionError> {
+  assert!(S > 0, "cannot index into a grid or chunk of size 0");
+  if local[0] >= S || local[1] >= S {
+    return Err(PositionError::LocalOutOfRange(local));
+  };
+
+  let compose_axis = |c: i32, l: usize| {
+    i64::checked_mul(c as i64, i64::try_from(S).ok()?)?.checked_add(l as i64)
+  };
+
+  match (compose_axis(chunk[0], local[0]), compose_axis(chunk[1], local[1])) {
+    (Some(x), Some(y)) => Ok([x, y]),
+    _ => Err(PositionError::GlobalOutOfRange(chunk, local))
+  }
+}
+
+/// An error describing a position which cannot be converted between global and chunk coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionError {
+  /// The chunk containing this global position lies outside of the range of [`ChunkPos`].
+  ChunkOutOfRange(GlobalPos),
+  /// This local position lies outside of the bounds of a chunk.
+  LocalOutOfRange(LocalPos),
+  /// The global position of this cell lies outside of the range of [`GlobalPos`].
+  GlobalOutOfRange(ChunkPos, LocalPos)
+}
+
+impl fmt::Display for PositionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PositionError::ChunkOutOfRange(pos) => {
+        write!(f, "the chunk containing position {}, {} is out of range", pos[0], pos[1])
+      },
+      PositionError::LocalOutOfRange(local) => {
+        write!(f, "local position {}, {} is outside of the chunk", local[0], local[1])
+      },
+      PositionError::GlobalOutOfRange(chunk, local) => {
+        write!(f, "local position {}, {} in chunk {}, {} is out of range", local[0], local[1], chunk[0], chunk[1])
+      }
+    }
+  }
+}
+
+impl std::error::Error for PositionError {}
+
 fn chunks_bounds<C, H>(chunks: &HashMap<ChunkPos, C, H>) -> Option<(ChunkPos, ChunkPos)> {
   chunks.keys().fold(None, |state, &chunk| match state {
     Some((min, max)) => Some((ChunkPos::min(min, chunk), ChunkPos::max(max, chunk))),
@@ -597,6 +1262,73 @@ fn chunks_bounds<C, H>(chunks: &HashMap<ChunkPos, C, H>) -> Option<(ChunkPos, Ch
   })
 }
 
+fn set_bit<H: BuildHasher, const S: usize>(chunks: &mut HashMap<ChunkPos, ChunkBits<S>, H>, chunk: ChunkPos, local: LocalPos, value: bool) -> bool {
+  match (chunks.get_mut(&chunk), value) {
+    (Some(chunk), value) => chunk.set(local, value),
+    (None, true) => chunks.entry(chunk).or_default().set(local, true),
+    (None, false) => false
+  }
+}
+
+fn decompose_cell<T, const S: usize>((pos, value): (GlobalPos, T)) -> (ChunkPos, LocalPos, T) {
+  let (chunk, local) = decompose::<S>(pos);
+  (chunk, local, value)
+}
+
+/// Collects references to every chunk between `min` and `max` (inclusive), in row-major order.
+fn rect_chunks<C, H: BuildHasher>(chunks: &HashMap<ChunkPos, C, H>, min: ChunkPos, max: ChunkPos) -> Vec<Option<&C>> {
+  (min[1]..=max[1])
+    .flat_map(|y| (min[0]..=max[0]).map(move |x| [x, y]))
+    .map(|pos| chunks.get(&pos))
+    .collect()
+}
+
+/// Collects mutable references to every chunk between `min` and `max` (inclusive), in row-major order.
+/// Since the map cannot hand out several mutable references by key, this visits every chunk once.
+fn rect_chunks_mut<C, H>(chunks: &mut HashMap<ChunkPos, C, H>, min: ChunkPos, max: ChunkPos) -> Vec<Option<&mut C>> {
+  let width = max[0].abs_diff(min[0]) as usize + 1;
+  let height = max[1].abs_diff(min[1]) as usize + 1;
+  let mut out = std::iter::repeat_with(|| None).take(width * height).collect::<Vec<_>>();
+  for (pos, chunk) in chunks.iter_mut() {
+    if (min[0]..=max[0]).contains(&pos[0]) && (min[1]..=max[1]).contains(&pos[1]) {
+      let index = pos[1].abs_diff(min[1]) as usize * width + pos[0].abs_diff(min[0]) as usize;
+      out[index] = Some(chunk);
+    };
+  };
+
+  out
+}
+
+/// Collects references to every existing chunk between `min` and `max` (inclusive), in row-major order.
+/// Looks up each position in the rectangle if there are fewer positions than chunks, otherwise visits every chunk once.
+fn existing_rect_chunks<C, H: BuildHasher>(chunks: &HashMap<ChunkPos, C, H>, min: ChunkPos, max: ChunkPos) -> Vec<(ChunkPos, &C)> {
+  let width = max[0].abs_diff(min[0]) as usize + 1;
+  let height = max[1].abs_diff(min[1]) as usize + 1;
+  if width.checked_mul(height).is_some_and(|area| area <= chunks.len()) {
+    return (min[1]..=max[1])
+      .flat_map(|y| (min[0]..=max[0]).map(move |x| [x, y]))
+      .filter_map(|pos| chunks.get(&pos).map(|chunk| (pos, chunk)))
+      .collect();
+  };
+
+  let mut out = chunks.iter()
+    .filter(|(pos, _)| (min[0]..=max[0]).contains(&pos[0]) && (min[1]..=max[1]).contains(&pos[1]))
+    .map(|(&pos, chunk)| (pos, chunk))
+    .collect::<Vec<_>>();
+  out.sort_unstable_by_key(|&([x, y], _)| (y, x));
+  out
+}
+
+/// Collects mutable references to every existing chunk between `min` and `max` (inclusive), in row-major order.
+fn existing_rect_chunks_mut<C, H>(chunks: &mut HashMap<ChunkPos, C, H>, min: ChunkPos, max: ChunkPos) -> Vec<(ChunkPos, &mut C)> {
+  let mut out = chunks.iter_mut()
+    .filter(|(pos, _)| (min[0]..=max[0]).contains(&pos[0]) && (min[1]..=max[1]).contains(&pos[1]))
+    .map(|(&pos, chunk)| (pos, chunk))
+    .collect::<Vec<_>>();
+  out.sort_unstable_by_key(|&([x, y], _)| (y, x));
+  out
+}
+
 fn map_total_bounds<const S: usize>((min, max): (ChunkPos, ChunkPos)) -> (GlobalPos, GlobalPos) {
   (compose::<S>(min, [0; 2]), compose::<S>(max, [S - 1; 2]))
 }
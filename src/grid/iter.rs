@@ -1,4 +1,6 @@
-use super::{ExGrid, ExGridSparse};
+#![allow(clippy::type_complexity)]
+
+use super::{ExGrid, ExGridBits, ExGridSparse};
 use crate::{GlobalPos, ChunkPos, LocalPos};
 use crate::chunk::*;
 
@@ -10,7 +12,10 @@ use std::collections::hash_map::{
   ValuesMut as HashMapValuesMut,
   IntoValues as HashMapIntoValues
 };
-use std::iter::{Flatten, FlatMap, FusedIterator};
+use std::hash::BuildHasher;
+use std::iter::{Flatten, FlatMap, FilterMap, Map, FusedIterator};
+use std::ops::Range;
+use std::slice::{Iter as SliceIter, IterMut as SliceIterMut};
 
 #[repr(transparent)]
 #[derive(Debug, Clone)]
@@ -114,6 +119,100 @@ impl<T, const S: usize> ExGridSparseIntoCells<T, S> {
 
 impl_iterator_no_double_ended!(ExGridSparseIntoCells, <T, S>, (GlobalPos, T));
 
+/// An iterator over every position within a rectangle of a sparse grid, in row-major order.
+/// Yields the position of the cell along with a reference to the cell's value,
+/// or `None` if the cell or the chunk it is located in is empty.
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+pub struct ExGridSparseWindow<'a, T, const S: usize> {
+  inner: Map<
+    Window<SliceIter<'a, [Option<T>; S]>, SliceIter<'a, Option<T>>, S>,
+    super::FilterSparseWindow<T>
+  >
+}
+
+impl<'a, T, const S: usize> ExGridSparseWindow<'a, T, S> {
+  pub(crate) fn new<H: BuildHasher>(grid: &'a ExGridSparse<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = Window::new(min, max, |cmin, cmax| super::rect_chunks(&grid.chunks, cmin, cmax), |chunk, y| {
+      chunk.as_chunk().rows_from(y)
+    });
+
+    ExGridSparseWindow { inner: inner.map(ExGridSparse::<T, S, H>::NEW_SPARSE_WINDOW) }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridSparseWindow, <'a, T, S>, (GlobalPos, Option<&'a T>));
+
+/// An iterator over every position within a rectangle of a sparse grid, in row-major order.
+/// Yields the position of the cell along with a mutable reference to the cell's value,
+/// or `None` if the cell or the chunk it is located in is empty.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct ExGridSparseWindowMut<'a, T, const S: usize> {
+  inner: Map<
+    Window<SliceIterMut<'a, [Option<T>; S]>, SliceIterMut<'a, Option<T>>, S>,
+    super::FilterSparseWindowMut<T>
+  >
+}
+
+impl<'a, T, const S: usize> ExGridSparseWindowMut<'a, T, S> {
+  pub(crate) fn new<H>(grid: &'a mut ExGridSparse<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = Window::new(min, max, |cmin, cmax| super::rect_chunks_mut(&mut grid.chunks, cmin, cmax), |chunk, y| {
+      chunk.as_chunk_mut().rows_from_mut(y)
+    });
+
+    ExGridSparseWindowMut { inner: inner.map(ExGridSparse::<T, S, H>::NEW_SPARSE_WINDOW_MUT) }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridSparseWindowMut, <'a, T, S>, (GlobalPos, Option<&'a mut T>));
+
+/// An iterator over all of the occupied cells within a rectangle of a sparse grid, in row-major order.
+/// Yields the position of the cell along with a reference to the cell's value.
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+pub struct ExGridSparseCellsInRect<'a, T, const S: usize> {
+  inner: FilterMap<
+    RectCells<SliceIter<'a, [Option<T>; S]>, SliceIter<'a, Option<T>>, S>,
+    super::FilterSparseCellsInRect<T>
+  >
+}
+
+impl<'a, T, const S: usize> ExGridSparseCellsInRect<'a, T, S> {
+  pub(crate) fn new<H: BuildHasher>(grid: &'a ExGridSparse<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = RectCells::new(min, max, |cmin, cmax| super::existing_rect_chunks(&grid.chunks, cmin, cmax), |chunk, y| {
+      chunk.as_chunk().rows_from(y)
+    });
+
+    ExGridSparseCellsInRect { inner: inner.filter_map(ExGridSparse::<T, S, H>::NEW_SPARSE_CELLS_IN_RECT) }
+  }
+}
+
+impl_iterator_no_double_ended!(ExGridSparseCellsInRect, <'a, T, S>, (GlobalPos, &'a T));
+
+/// An iterator over all of the occupied cells within a rectangle of a sparse grid, in row-major order.
+/// Yields the position of the cell along with a mutable reference to the cell's value.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct ExGridSparseCellsInRectMut<'a, T, const S: usize> {
+  inner: FilterMap<
+    RectCells<SliceIterMut<'a, [Option<T>; S]>, SliceIterMut<'a, Option<T>>, S>,
+    super::FilterSparseCellsInRectMut<T>
+  >
+}
+
+impl<'a, T, const S: usize> ExGridSparseCellsInRectMut<'a, T, S> {
+  pub(crate) fn new<H>(grid: &'a mut ExGridSparse<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = RectCells::new(min, max, |cmin, cmax| super::existing_rect_chunks_mut(&mut grid.chunks, cmin, cmax), |chunk, y| {
+      chunk.as_chunk_mut().rows_from_mut(y)
+    });
+
+    ExGridSparseCellsInRectMut { inner: inner.filter_map(ExGridSparse::<T, S, H>::NEW_SPARSE_CELLS_IN_RECT_MUT) }
+  }
+}
+
+impl_iterator_no_double_ended!(ExGridSparseCellsInRectMut, <'a, T, S>, (GlobalPos, &'a mut T));
+
 
 
 #[repr(transparent)]
@@ -218,6 +317,76 @@ impl<T, const S: usize> ExGridIntoCells<T, S> {
 
 impl_iterator_no_double_ended!(ExGridIntoCells, <T, S>, (GlobalPos, T));
 
+/// An iterator over every position within a rectangle of a grid, in row-major order.
+/// Yields the position of the cell along with a reference to the cell's value,
+/// or `None` if the chunk it is located in does not exist.
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+pub struct ExGridWindow<'a, T, const S: usize> {
+  inner: Window<SliceIter<'a, [T; S]>, SliceIter<'a, T>, S>
+}
+
+impl<'a, T, const S: usize> ExGridWindow<'a, T, S> {
+  pub(crate) fn new<H: BuildHasher>(grid: &'a ExGrid<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = Window::new(min, max, |cmin, cmax| super::rect_chunks(&grid.chunks, cmin, cmax), Chunk::rows_from);
+    ExGridWindow { inner }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridWindow, <'a, T, S>, (GlobalPos, Option<&'a T>));
+
+/// An iterator over every position within a rectangle of a grid, in row-major order.
+/// Yields the position of the cell along with a mutable reference to the cell's value,
+/// or `None` if the chunk it is located in does not exist.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct ExGridWindowMut<'a, T, const S: usize> {
+  inner: Window<SliceIterMut<'a, [T; S]>, SliceIterMut<'a, T>, S>
+}
+
+impl<'a, T, const S: usize> ExGridWindowMut<'a, T, S> {
+  pub(crate) fn new<H>(grid: &'a mut ExGrid<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = Window::new(min, max, |cmin, cmax| super::rect_chunks_mut(&mut grid.chunks, cmin, cmax), Chunk::rows_from_mut);
+    ExGridWindowMut { inner }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridWindowMut, <'a, T, S>, (GlobalPos, Option<&'a mut T>));
+
+/// An iterator over all of the cells within a rectangle of a grid whose chunks exist, in row-major order.
+/// Yields the position of the cell along with a reference to the cell's value.
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+pub struct ExGridCellsInRect<'a, T, const S: usize> {
+  inner: RectCells<SliceIter<'a, [T; S]>, SliceIter<'a, T>, S>
+}
+
+impl<'a, T, const S: usize> ExGridCellsInRect<'a, T, S> {
+  pub(crate) fn new<H: BuildHasher>(grid: &'a ExGrid<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = RectCells::new(min, max, |cmin, cmax| super::existing_rect_chunks(&grid.chunks, cmin, cmax), Chunk::rows_from);
+    ExGridCellsInRect { inner }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridCellsInRect, <'a, T, S>, (GlobalPos, &'a T));
+
+/// An iterator over all of the cells within a rectangle of a grid whose chunks exist, in row-major order.
+/// Yields the position of the cell along with a mutable reference to the cell's value.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct ExGridCellsInRectMut<'a, T, const S: usize> {
+  inner: RectCells<SliceIterMut<'a, [T; S]>, SliceIterMut<'a, T>, S>
+}
+
+impl<'a, T, const S: usize> ExGridCellsInRectMut<'a, T, S> {
+  pub(crate) fn new<H>(grid: &'a mut ExGrid<T, S, H>, min: GlobalPos, max: GlobalPos) -> Self {
+    let inner = RectCells::new(min, max, |cmin, cmax| super::existing_rect_chunks_mut(&mut grid.chunks, cmin, cmax), Chunk::rows_from_mut);
+    ExGridCellsInRectMut { inner }
+  }
+}
+
+impl_iterator_exact_size_no_double_ended!(ExGridCellsInRectMut, <'a, T, S>, (GlobalPos, &'a mut T));
+
 
 
 macro_rules! map {
@@ -231,6 +400,46 @@ macro_rules! map {
   };
 }
 
+/// An iterator over all of the set cells in a bit grid.
+/// Yields the position of the cell.
+#[derive(Debug, Clone)]
+pub struct ExGridBitsCells<'a, const S: usize> {
+  chunks: HashMapIter<'a, ChunkPos, ChunkBits<S>>,
+  current: Option<(ChunkPos, ChunkBitsCells<S>)>
+}
+
+impl<'a, const S: usize> ExGridBitsCells<'a, S> {
+  pub(crate) fn new<H>(grid: &'a ExGridBits<S, H>) -> Self {
+    ExGridBitsCells { chunks: grid.chunks.iter(), current: None }
+  }
+}
+
+impl<'a, const S: usize> Iterator for ExGridBitsCells<'a, S> {
+  type Item = GlobalPos;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((chunk, cells)) = &mut self.current {
+        if let Some(local) = cells.next() {
+          return Some(super::compose::<S>(*chunk, local));
+        };
+      };
+
+      let (&chunk, bits) = self.chunks.next()?;
+      self.current = Some((chunk, bits.cells()));
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let current = self.current.as_ref().map_or(0, |(_, cells)| cells.len());
+    let remaining = self.chunks.len() * S * S;
+    (current, Some(current + remaining))
+  }
+}
+
+impl<'a, const S: usize> FusedIterator for ExGridBitsCells<'a, S> {}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Compose<I, const S: usize> {
   chunk: ChunkPos,
@@ -295,3 +504,269 @@ where I: DoubleEndedIterator<Item = (LocalPos, T)> + ExactSizeIterator {
 
 impl<T, I, const S: usize> FusedIterator for Compose<I, S>
 where I: Iterator<Item = (LocalPos, T)> + FusedIterator {}
+
+
+
+/// A row of cells within a chunk which can be narrowed down to a range of columns.
+pub(crate) trait WindowRow {
+  type Cells: Iterator;
+
+  fn cells(self, range: Range<usize>) -> Self::Cells;
+}
+
+impl<'a, T, const S: usize> WindowRow for &'a [T; S] {
+  type Cells = SliceIter<'a, T>;
+
+  #[inline]
+  fn cells(self, range: Range<usize>) -> Self::Cells {
+    self[range].iter()
+  }
+}
+
+impl<'a, T, const S: usize> WindowRow for &'a mut [T; S] {
+  type Cells = SliceIterMut<'a, T>;
+
+  #[inline]
+  fn cells(self, range: Range<usize>) -> Self::Cells {
+    self[range].iter_mut()
+  }
+}
+
+/// Walks every position within an inclusive rectangle in row-major order,
+/// yielding cells from the chunks overlapping the rectangle and `None` for any missing chunks.
+/// Each chunk is looked up once, after which cells are read row by row from that chunk.
+#[derive(Debug, Clone)]
+pub(crate) struct Window<I, C, const S: usize> {
+  /// Row iterators for every chunk overlapping the rectangle, in row-major order.
+  rows: Vec<Option<I>>,
+  /// The run of cells currently being yielded, ending at the edge of a chunk or the rectangle.
+  cells: Option<C>,
+  chunks_min: ChunkPos,
+  chunks_max: ChunkPos,
+  chunks_width: usize,
+  min: GlobalPos,
+  max: GlobalPos,
+  pos: GlobalPos,
+  remaining: usize
+}
+
+impl<I, C, const S: usize> Window<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {
+  /// Creates a new window, `chunks` must return the chunks between two chunk positions (inclusive),
+  /// in row-major order, and `rows_from` must return an iterator over a chunk's rows, starting at a given row.
+  pub(crate) fn new<R>(
+    min: GlobalPos, max: GlobalPos,
+    chunks: impl FnOnce(ChunkPos, ChunkPos) -> Vec<Option<R>>,
+    rows_from: impl Fn(R, usize) -> I
+  ) -> Self {
+    // Chunks beyond the range of chunk positions can never exist, so the chunks are clipped to the range,
+    // and any positions beyond it are yielded as missing
+    let chunk_pos = |pos: GlobalPos| pos.map(|p| p.div_euclid(S as i64).clamp(i32::MIN.into(), i32::MAX.into()) as i32);
+    let (chunks_min, chunks_max) = (chunk_pos(min), chunk_pos(max));
+    if min[0] > max[0] || min[1] > max[1] {
+      return Window { rows: Vec::new(), cells: None, chunks_min, chunks_max, chunks_width: 0, min, max, pos: min, remaining: 0 };
+    };
+
+    let width = usize::try_from(max[0].abs_diff(min[0]) + 1).ok();
+    let height = usize::try_from(max[1].abs_diff(min[1]) + 1).ok();
+    let remaining = Option::zip(width, height)
+      .and_then(|(width, height)| usize::checked_mul(width, height))
+      .expect("rectangle contains too many cells to iterate over");
+
+    let first_y = min[1].saturating_sub(super::compose::<S>(chunks_min, [0; 2])[1]).clamp(0, S as i64) as usize;
+    let chunks_width = chunks_max[0].abs_diff(chunks_min[0]) as usize + 1;
+    let rows = chunks(chunks_min, chunks_max).into_iter()
+      .enumerate()
+      .map(|(i, chunk)| {
+        // Only chunks in the first row of chunks start part way through
+        let y = if i < chunks_width { first_y } else { 0 };
+        chunk.map(|chunk| rows_from(chunk, y))
+      })
+      .collect();
+
+    Window { rows, cells: None, chunks_min, chunks_max, chunks_width, min, max, pos: min, remaining }
+  }
+
+  /// Starts a new run of cells at `pos`, returning the first cell, or `None` if its chunk does not exist.
+  fn next_run(&mut self, pos: GlobalPos) -> Option<C::Item> {
+    let chunk = pos.map(|p| p.div_euclid(S as i64));
+    let in_range = (0..2).all(|i| (i64::from(self.chunks_min[i])..=i64::from(self.chunks_max[i])).contains(&chunk[i]));
+    if !in_range {
+      return None;
+    };
+
+    let (chunk, local) = (chunk.map(|c| c as i32), pos.map(|p| p.rem_euclid(S as i64) as usize));
+    let index = chunk[1].abs_diff(self.chunks_min[1]) as usize * self.chunks_width
+      + chunk[0].abs_diff(self.chunks_min[0]) as usize;
+    let row = self.rows[index].as_mut()?.next()
+      .expect("chunk row should be present for every row of the rectangle");
+    let len = usize::min(S - local[0], self.max[0].abs_diff(pos[0]) as usize + 1);
+    let mut cells = row.cells(local[0]..local[0] + len);
+    let value = cells.next();
+    self.cells = Some(cells);
+    value
+  }
+}
+
+impl<I, C, const S: usize> Iterator for Window<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {
+  type Item = (GlobalPos, Option<C::Item>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    };
+
+    let pos = self.pos;
+    let value = match self.cells.as_mut().and_then(Iterator::next) {
+      Some(value) => Some(value),
+      None => self.next_run(pos)
+    };
+
+    self.remaining -= 1;
+    if pos[0] == self.max[0] {
+      self.pos = [self.min[0], pos[1].wrapping_add(1)];
+    } else {
+      self.pos = [pos[0] + 1, pos[1]];
+    };
+
+    Some((pos, value))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<I, C, const S: usize> ExactSizeIterator for Window<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {}
+
+impl<I, C, const S: usize> FusedIterator for Window<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {}
+
+/// Walks the cells of every existing chunk overlapping an inclusive rectangle in row-major order.
+/// Unlike [`Window`], missing chunks are skipped entirely, so the cost depends only on the chunks that exist.
+#[derive(Debug, Clone)]
+pub(crate) struct RectCells<I, C, const S: usize> {
+  /// Row iterators for every existing chunk overlapping the rectangle, sorted into row-major order.
+  chunks: Vec<(ChunkPos, I)>,
+  /// The range of `chunks` which lie in the current row of chunks.
+  row: Range<usize>,
+  /// The index of the next chunk in `chunks` to read the current row of cells from.
+  index: usize,
+  /// The run of cells currently being yielded, along with the position of the next cell.
+  cells: Option<(GlobalPos, C)>,
+  min: GlobalPos,
+  max: GlobalPos,
+  /// The current row of cells, and the last row of cells in the current row of chunks.
+  y: i64,
+  row_max_y: i64,
+  remaining: usize
+}
+
+impl<I, C, const S: usize> RectCells<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {
+  /// Creates a new iterator, `chunks` must return the existing chunks between two chunk positions (inclusive),
+  /// in row-major order, and `rows_from` must return an iterator over a chunk's rows, starting at a given row.
+  pub(crate) fn new<R>(
+    min: GlobalPos, max: GlobalPos,
+    chunks: impl FnOnce(ChunkPos, ChunkPos) -> Vec<(ChunkPos, R)>,
+    rows_from: impl Fn(R, usize) -> I
+  ) -> Self {
+    let chunks = if min[0] > max[0] || min[1] > max[1] {
+      Vec::new()
+    } else {
+      // Chunks beyond the range of chunk positions can never exist, so the rectangle is clipped to the range
+      let chunk_pos = |pos: GlobalPos| pos.map(|p| p.div_euclid(S as i64).clamp(i32::MIN.into(), i32::MAX.into()) as i32);
+      chunks(chunk_pos(min), chunk_pos(max))
+    };
+
+    let mut remaining = 0;
+    let chunks = chunks.into_iter()
+      .map(|(chunk_pos, chunk)| {
+        let [x, y] = Self::overlap(chunk_pos, min, max);
+        remaining += x.len() * y.len();
+        (chunk_pos, rows_from(chunk, y.start))
+      })
+      .collect();
+
+    RectCells { chunks, row: 0..0, index: 0, cells: None, min, max, y: min[1], row_max_y: min[1], remaining }
+  }
+
+  /// Returns the range of local coordinates along each axis of a chunk which lie within the rectangle.
+  fn overlap(chunk_pos: ChunkPos, min: GlobalPos, max: GlobalPos) -> [Range<usize>; 2] {
+    let origin = super::compose::<S>(chunk_pos, [0; 2]);
+    std::array::from_fn(|axis| {
+      let start = min[axis].saturating_sub(origin[axis]).clamp(0, S as i64) as usize;
+      let end = max[axis].saturating_sub(origin[axis]).saturating_add(1).clamp(0, S as i64) as usize;
+      start..end
+    })
+  }
+
+  /// Moves on to the next run of cells, returning false once every chunk has been read.
+  fn next_run(&mut self) -> bool {
+    if self.index == self.row.end {
+      if !self.row.is_empty() && self.y < self.row_max_y {
+        // Read the next row of cells from the same row of chunks
+        self.y += 1;
+        self.index = self.row.start;
+      } else if self.row.end < self.chunks.len() {
+        // Move on to the next row of chunks
+        let start = self.row.end;
+        let chunk_pos = self.chunks[start].0;
+        let len = self.chunks[start..].iter().take_while(|(other, _)| other[1] == chunk_pos[1]).count();
+        let [_, y] = Self::overlap(chunk_pos, self.min, self.max);
+        let origin = super::compose::<S>(chunk_pos, [0; 2]);
+        self.row = start..start + len;
+        self.index = start;
+        self.y = origin[1] + y.start as i64;
+        self.row_max_y = origin[1] + y.end as i64 - 1;
+      } else {
+        return false;
+      };
+    };
+
+    let (chunk_pos, rows) = &mut self.chunks[self.index];
+    let [x, _] = Self::overlap(*chunk_pos, self.min, self.max);
+    let pos = super::compose::<S>(*chunk_pos, [x.start, 0]);
+    let row = rows.next().expect("chunk row should be present for every row of the rectangle");
+    self.cells = Some(([pos[0], self.y], row.cells(x)));
+    self.index += 1;
+    true
+  }
+}
+
+impl<I, C, const S: usize> Iterator for RectCells<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {
+  type Item = (GlobalPos, C::Item);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((pos, cells)) = &mut self.cells {
+        if let Some(value) = cells.next() {
+          let current = *pos;
+          pos[0] += 1;
+          self.remaining -= 1;
+          return Some((current, value));
+        };
+      };
+
+      if !self.next_run() {
+        self.cells = None;
+        return None;
+      };
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<I, C, const S: usize> ExactSizeIterator for RectCells<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {}
+
+impl<I, C, const S: usize> FusedIterator for RectCells<I, C, S>
+where I: Iterator, I::Item: WindowRow<Cells = C>, C: Iterator {}
@@ -6,12 +6,13 @@ mod macros;
 #[cfg(feature = "automata")]
 pub mod automata;
 pub mod chunk;
+pub mod filter;
 pub mod grid;
 mod vector;
 
-pub use crate::chunk::{Chunk, ChunkSparse};
-pub use crate::grid::{ExGrid, ExGridSparse};
-pub use crate::vector::Lerp;
+pub use crate::chunk::{Chunk, ChunkBits, ChunkSparse};
+pub use crate::grid::{ExGrid, ExGridBits, ExGridSparse};
+pub use crate::vector::{Cubic, Lerp, SampleFloat};
 
 pub type GlobalPos = [i64; 2];
 pub type ChunkPos = [i32; 2];
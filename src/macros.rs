@@ -99,6 +99,25 @@ macro_rules! impl_iterator_known_size_no_double_ended {
   };
 }
 
+macro_rules! impl_iterator_exact_size_no_double_ended {
+  ($Struct:ident, <'a, T, S>, $Item:ty) => {
+    impl<'a, T, const S: usize> Iterator for $Struct<'a, T, S> {
+      type Item = $Item;
+
+      impl_iterator_methods!();
+
+      #[inline]
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+      }
+    }
+
+    impl<'a, T, const S: usize> ExactSizeIterator for $Struct<'a, T, S> {}
+
+    impl<'a, T, const S: usize> FusedIterator for $Struct<'a, T, S> {}
+  };
+}
+
 macro_rules! impl_iterator {
   ($Struct:ident, <'a, T, S>, $Item:ty $(, $size_hint:expr)?) => {
     impl_iterator_no_double_ended!($Struct, <'a, T, S>, $Item $(, $size_hint)?);
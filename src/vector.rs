@@ -1,6 +1,8 @@
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg};
 
-use num_traits::AsPrimitive;
+use num_traits::{AsPrimitive, Float};
+
+use std::fmt::Display;
 
 macro_rules! operator_impl {
   ($Vector:ident, $Op:ident, $op:ident, $SIZE:literal) => {
@@ -41,6 +43,7 @@ macro_rules! vector_impl {
 
     impl<T> $Vector<T> {
       #[inline]
+      #[cfg_attr(not(feature = "automata"), allow(dead_code))]
       pub const fn new($($f: T),*) -> Self {
         $Vector { $($f),* }
       }
@@ -107,6 +110,15 @@ macro_rules! vector_impl {
 
 vector_impl!(pub(crate) struct Vector2 { x, y }: 2);
 
+/// A floating point type which can be used to describe positions when sampling.
+/// Using `f64` allows positions to remain precise far past the point at which
+/// `f32` can no longer represent every integer coordinate (±2^24).
+pub trait SampleFloat: Float + Display + AsPrimitive<i64> + AsPrimitive<usize> {}
+
+impl SampleFloat for f32 {}
+
+impl SampleFloat for f64 {}
+
 pub trait Lerp<Factor = f32> {
   type Output;
 
@@ -128,3 +140,163 @@ impl Lerp<f64> for f64 {
     from.mul_add(1.0 - factor, to * factor)
   }
 }
+
+macro_rules! lerp_impl_int {
+  ($($Int:ty => $to_ordered:expr, $from_ordered:expr);* $(;)?) => {
+    $(lerp_impl_int!(@ $Int, f32, $to_ordered, $from_ordered);)*
+    $(lerp_impl_int!(@ $Int, f64, $to_ordered, $from_ordered);)*
+  };
+  (@ $Int:ty, $Float:ty, $to_ordered:expr, $from_ordered:expr) => {
+    impl Lerp<$Float> for $Int {
+      type Output = $Int;
+
+      /// Interpolates exactly, rounding halfway values towards `to`.
+      /// Factors outside of `0..=1` are clamped, so the result always lies between `from` and `to`.
+      fn lerp(from: Self, to: Self, factor: $Float) -> Self::Output {
+        let to_ordered: fn($Int) -> u128 = $to_ordered;
+        let from_ordered: fn(u128) -> $Int = $from_ordered;
+        from_ordered(lerp_ordered(to_ordered(from), to_ordered(to), factor as f64))
+      }
+    }
+  };
+}
+
+lerp_impl_int! {
+  u8 => |v| v as u128, |v| v as u8;
+  u16 => |v| v as u128, |v| v as u16;
+  u32 => |v| v as u128, |v| v as u32;
+  u64 => |v| v as u128, |v| v as u64;
+  u128 => |v| v, |v| v;
+  usize => |v| v as u128, |v| v as usize;
+  i8 => |v| v as i128 as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128 as i8;
+  i16 => |v| v as i128 as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128 as i16;
+  i32 => |v| v as i128 as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128 as i32;
+  i64 => |v| v as i128 as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128 as i64;
+  i128 => |v| v as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128;
+  isize => |v| v as i128 as u128 ^ SIGN_BIT, |v| (v ^ SIGN_BIT) as i128 as isize;
+}
+
+/// Flips signed integers which have been cast to `u128` so that they keep their order.
+const SIGN_BIT: u128 = 1 << 127;
+
+/// Interpolates between two integers which have been mapped onto `u128` without changing their order.
+/// The factor is converted to a 63-bit fixed point fraction, so that no precision is lost for any integer type.
+fn lerp_ordered(from: u128, to: u128, factor: f64) -> u128 {
+  const SHIFT: u32 = 63;
+  let factor = (factor.clamp(0.0, 1.0) * (1u64 << SHIFT) as f64).round() as u128;
+  // Multiplies the halves of `diff` separately, since the full product may not fit in a `u128`
+  let scale = |diff: u128| {
+    let (hi, lo) = (diff >> 64, diff & u128::from(u64::MAX));
+    ((hi * factor) << (64 - SHIFT)) + ((lo * factor + (1 << (SHIFT - 1))) >> SHIFT)
+  };
+
+  if to >= from { from + scale(to - from) } else { from - scale(from - to) }
+}
+
+impl<T, F, const N: usize> Lerp<F> for [T; N]
+where T: Lerp<F>, F: Copy {
+  type Output = [T::Output; N];
+
+  /// Interpolates each element of the array separately.
+  fn lerp(from: Self, to: Self, factor: F) -> Self::Output {
+    let mut to = to.into_iter();
+    from.map(|from| T::lerp(from, to.next().expect("arrays should be the same length"), factor))
+  }
+}
+
+macro_rules! lerp_impl_tuple {
+  ($($T:ident $from:ident $to:ident),+) => {
+    impl<F: Copy, $($T: Lerp<F>),+> Lerp<F> for ($($T,)+) {
+      type Output = ($($T::Output,)+);
+
+      /// Interpolates each element of the tuple separately.
+      fn lerp(from: Self, to: Self, factor: F) -> Self::Output {
+        let ($($from,)+) = from;
+        let ($($to,)+) = to;
+        ($($T::lerp($from, $to, factor),)+)
+      }
+    }
+  };
+}
+
+lerp_impl_tuple!(A a0 a1);
+lerp_impl_tuple!(A a0 a1, B b0 b1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1, D d0 d1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1, D d0 d1, E e0 e1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1, D d0 d1, E e0 e1, G g0 g1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1, D d0 d1, E e0 e1, G g0 g1, H h0 h1);
+lerp_impl_tuple!(A a0 a1, B b0 b1, C c0 c1, D d0 d1, E e0 e1, G g0 g1, H h0 h1, I i0 i1);
+
+impl<T, F> Lerp<F> for Option<T>
+where T: Lerp<F> {
+  type Output = Option<T::Output>;
+
+  /// Interpolates between two values only if both are present.
+  fn lerp(from: Self, to: Self, factor: F) -> Self::Output {
+    Some(T::lerp(from?, to?, factor))
+  }
+}
+
+/// Implements [`Lerp`] for a struct by interpolating each of the listed fields separately.
+/// Every field must implement `Lerp` with an `Output` of its own type.
+///
+/// By default, this implements `Lerp<f32>` and `Lerp<f64>`,
+/// specific factor types may be given after the field list instead.
+///
+/// ```
+/// # use exgrid::Lerp;
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Color { r: u8, g: u8, b: u8 }
+///
+/// exgrid::impl_lerp!(Color { r, g, b });
+///
+/// let black = Color { r: 0, g: 0, b: 0 };
+/// let white = Color { r: 255, g: 255, b: 255 };
+/// assert_eq!(Color::lerp(black, white, 0.5f32), Color { r: 128, g: 128, b: 128 });
+/// ```
+#[macro_export]
+macro_rules! impl_lerp {
+  ($Struct:ident { $($field:ident),* $(,)? }) => {
+    $crate::impl_lerp!($Struct { $($field),* }: f32, f64);
+  };
+  ($Struct:ident $fields:tt: $($Factor:ty),+) => {
+    $($crate::impl_lerp!(@ $Struct $fields, $Factor);)+
+  };
+  (@ $Struct:ident { $($field:ident),* $(,)? }, $Factor:ty) => {
+    impl $crate::Lerp<$Factor> for $Struct {
+      type Output = $Struct;
+
+      fn lerp(from: Self, to: Self, factor: $Factor) -> Self::Output {
+        $Struct { $($field: $crate::Lerp::lerp(from.$field, to.$field, factor)),* }
+      }
+    }
+  };
+}
+
+/// Cubic interpolation between `p1` and `p2`, using `p0` and `p3` as control points.
+pub trait Cubic<Factor = f32> {
+  type Output;
+
+  fn cubic(p0: Self, p1: Self, p2: Self, p3: Self, factor: Factor) -> Self::Output;
+}
+
+macro_rules! cubic_impl_float {
+  ($Float:ty) => {
+    impl Cubic<$Float> for $Float {
+      type Output = $Float;
+
+      /// Catmull-Rom interpolation.
+      fn cubic(p0: Self, p1: Self, p2: Self, p3: Self, factor: $Float) -> Self::Output {
+        let a = 2.0 * p1;
+        let b = p2 - p0;
+        let c = 2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3;
+        let d = 3.0 * (p1 - p2) + p3 - p0;
+        0.5 * d.mul_add(factor, c).mul_add(factor, b).mul_add(factor, a)
+      }
+    }
+  };
+}
+
+cubic_impl_float!(f32);
+cubic_impl_float!(f64);
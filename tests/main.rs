@@ -1,6 +1,7 @@
 extern crate exgrid;
 
-use exgrid::GlobalPos;
+use exgrid::{Chunk, ChunkBits, GlobalPos, Lerp};
+use exgrid::filter::*;
 use exgrid::grid::*;
 #[cfg(feature = "serde")]
 use serde::de::DeserializeOwned;
@@ -31,6 +32,60 @@ fn test_translate_g<const S: usize>() {
   };
 }
 
+#[test]
+fn test_translate_checked() {
+  perform_test_g!(test_translate_checked_g);
+}
+
+fn test_translate_checked_g<const S: usize>() {
+  for c1 in random_positions() {
+    let d1 = try_decompose::<S>(c1).unwrap();
+    assert_eq!(d1, decompose::<S>(c1));
+    assert_eq!(try_compose::<S>(d1.0, d1.1), Ok(c1));
+  };
+
+  let far = [i64::MAX, 0];
+  assert_eq!(try_decompose::<S>(far), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(try_compose::<S>([0, 0], [S, 0]), Err(PositionError::LocalOutOfRange([S, 0])));
+
+  let mut grid = ExGridSparse::<u32, S>::new();
+  assert_eq!(grid.try_insert(far, 1), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(grid.try_insert([1, 2], 1), Ok(None));
+  assert_eq!(grid.try_get([1, 2]), Ok(Some(&1)));
+  assert!(grid.try_get(far).is_err());
+
+  assert_eq!(grid.try_insert([1, 2], 2), Ok(Some(1)));
+  assert_eq!(grid.try_get_mut(far), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(grid.try_get_mut([1, 2]), Ok(Some(&mut 2)));
+  assert_eq!(grid.remove(far), None);
+  assert_eq!(grid.chunks_count(), 1);
+
+  let mut grid = ExGrid::<u32, S>::new();
+  assert_eq!(grid.try_insert_default(far, 1), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(grid.try_insert_default([1, 2], 1), Ok(0));
+  assert_eq!(grid.try_insert_default([1, 2], 2), Ok(1));
+  assert_eq!(grid.try_get_mut([1, 2]), Ok(Some(&mut 2)));
+  assert_eq!(grid.try_get([1, 2]), Ok(Some(&2)));
+  assert!(grid.try_get(far).is_err());
+  assert_eq!(grid.chunks_count(), 1);
+
+  let mut grid = ExGridBits::<S>::new();
+  assert_eq!(grid.try_set(far, true), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(grid.try_get(far), Err(PositionError::ChunkOutOfRange(far)));
+  assert_eq!(grid.chunks_count(), 0);
+
+  // Without checked positions, the existing accessors silently wrap around to another chunk
+  #[cfg(not(feature = "checked-positions"))]
+  assert_eq!(decompose::<S>([(1 << 31) * S as i64, 0]), decompose::<S>([-(1 << 31) * S as i64, 0]));
+}
+
+#[cfg(all(feature = "checked-positions", debug_assertions))]
+#[test]
+#[should_panic]
+fn test_checked_positions() {
+  ExGridSparse::<u32, 16>::new().insert([i64::MAX, 0], 1);
+}
+
 #[test]
 fn test_grid_basics() {
   perform_test_g!(test_grid_basics_g);
@@ -55,6 +110,8 @@ fn test_grid_basics_g<const S: usize>() {
     assert_eq!(grid.get(pos), Some(&value), "{pos:?}");
   };
 
+  assert_eq!(grid.clone().into_cells().collect::<ExGrid<u32, S>>(), grid);
+
   #[cfg(feature = "serde")]
   test_serde_roundtrip(&grid);
 }
@@ -83,8 +140,1189 @@ fn test_grid_sparse_basics_g<const S: usize>() {
     assert_eq!(grid.get(pos), Some(&value), "{pos:?}");
   };
 
+  assert_eq!(grid.clone().into_cells().collect::<ExGridSparse<u32, S>>(), grid);
+
+  let mut extended = ExGridSparse::<u32, S>::new();
+  extended.extend(random_elements().chain(grid.clone().into_cells()));
+  for (pos, &value) in grid.cells() {
+    assert_eq!(extended.get(pos), Some(&value), "{pos:?}");
+  };
+
+  #[cfg(feature = "serde")]
+  test_serde_roundtrip(&grid);
+}
+
+#[test]
+fn test_grid_bits() {
+  perform_test_g!(test_grid_bits_g);
+}
+
+fn test_grid_bits_g<const S: usize>() {
+  let mut grid = ExGridBits::<S>::new();
+  let mut grid_sparse = ExGridSparse::<(), S>::new();
+  for pos in random_positions() {
+    assert_eq!(grid.set(pos, true), grid_sparse.insert(pos, ()).is_some());
+  };
+
+  assert!(!grid.set([1 << 20, 1 << 20], false));
+  assert!(!grid.get([1 << 20, 1 << 20]));
+  assert_eq!(grid.chunks_count(), grid_sparse.chunks_count());
+  assert_eq!(grid.count_occupied(), grid_sparse.iter().count());
+
+  let mut cells = grid.cells().collect::<Vec<GlobalPos>>();
+  let mut cells_sparse = grid_sparse.cells().map(|(pos, _)| pos).collect::<Vec<GlobalPos>>();
+  cells.sort_unstable();
+  cells_sparse.sort_unstable();
+  assert_eq!(cells, cells_sparse);
+  assert_eq!(cells.iter().copied().collect::<ExGridBits<S>>(), grid);
+
+  assert_eq!(ExGridSparse::from(grid.clone()), grid_sparse);
+  assert_eq!(ExGridBits::from(grid_sparse.clone()), grid);
+  assert_eq!(ExGridBits::from(ExGrid::<bool, S>::from(grid.clone())), grid);
+
   #[cfg(feature = "serde")]
   test_serde_roundtrip(&grid);
+
+  for &pos in cells.iter() {
+    assert!(grid.set(pos, false));
+  };
+
+  assert!(grid.is_all_vacant());
+  grid.clean_up();
+  assert_eq!(grid.chunks_count(), 0);
+
+  let chunk = ChunkBits::<64>::from_rows([u64::MAX; 64]);
+  assert!(chunk.is_all_occupied());
+  assert_eq!(chunk.count_occupied(), 64 * 64);
+  assert_eq!(ChunkBits::<S>::from_rows([u64::MAX; S]).count_occupied(), S * S);
+}
+
+#[test]
+fn test_grid_sparse_remove() {
+  perform_test_g!(test_grid_sparse_remove_g);
+}
+
+fn test_grid_sparse_remove_g<const S: usize>() {
+  let mut grid = ExGridSparse::<u32, S>::new();
+  let elements = random_elements().collect::<Vec<_>>();
+  for &(pos, value) in elements.iter() {
+    grid.insert(pos, value);
+  };
+
+  let mut grid_pruned = grid.clone();
+  grid_pruned.set_prune_vacant_chunks(true);
+  assert!(grid_pruned.prunes_vacant_chunks());
+
+  let chunks_count = grid.chunks_count();
+  for &(pos, _) in elements.iter() {
+    let value = grid.get(pos).copied();
+    assert_eq!(grid.remove(pos), value);
+    assert_eq!(grid_pruned.remove(pos), value);
+    assert_eq!(grid.remove(pos), None);
+    assert_eq!(grid.get(pos), None);
+  };
+
+  assert_eq!(grid.remove([i64::MAX, i64::MAX]), None);
+  assert_eq!(grid.chunks_count(), chunks_count);
+  assert!(grid.is_all_vacant());
+  assert_eq!(grid_pruned.chunks_count(), 0);
+}
+
+#[test]
+fn test_grid_entry() {
+  perform_test_g!(test_grid_entry_g);
+}
+
+fn test_grid_entry_g<const S: usize>() {
+  let mut grid = ExGrid::<u32, S>::new();
+  let mut grid_sparse = ExGridSparse::<u32, S>::new();
+  grid_sparse.set_prune_vacant_chunks(true);
+  for (pos, value) in random_elements() {
+    assert_eq!(grid.entry(pos).key(), pos);
+    *grid.entry(pos).and_modify(|v| *v += 1).or_default() = value;
+    assert_eq!(grid.get(pos), Some(&value));
+
+    let previous = grid_sparse.get(pos).copied();
+    match grid_sparse.entry(pos) {
+      ExGridSparseEntry::Occupied(mut entry) => {
+        assert_eq!(entry.key(), pos);
+        assert_eq!(Some(entry.insert(value)), previous);
+        assert_eq!(entry.get(), &value);
+      },
+      ExGridSparseEntry::Vacant(entry) => {
+        assert_eq!(entry.key(), pos);
+        assert_eq!(previous, None);
+        assert_eq!(entry.insert(value), &value);
+      }
+    };
+
+    assert_eq!(grid_sparse.entry(pos).and_modify(|v| *v ^= 1).or_insert(0), &(value ^ 1));
+  };
+
+  let positions = grid_sparse.cells().map(|(pos, _)| pos).collect::<Vec<_>>();
+  for pos in positions {
+    match grid_sparse.entry(pos) {
+      ExGridSparseEntry::Occupied(entry) => assert_eq!(entry.remove_entry().0, pos),
+      ExGridSparseEntry::Vacant(..) => panic!("{pos:?} should be occupied")
+    };
+  };
+
+  assert_eq!(grid_sparse.chunks_count(), 0);
+}
+
+#[test]
+fn test_sample_far() {
+  perform_test_g!(test_sample_far_g);
+}
+
+fn test_sample_far_g<const S: usize>() {
+  // Far beyond the precision of f32, but still within the range of chunk positions
+  let x = i64::from(i32::MAX) * S as i64 / 2 + 1;
+  let mut grid = ExGrid::<f64, S>::new();
+  *grid.get_mut_default([x, 0]) = 1.0;
+  *grid.get_mut_default([x + 1, 0]) = 3.0;
+  *grid.get_mut_default([x, 1]) = 5.0;
+  *grid.get_mut_default([x + 1, 1]) = 7.0;
+  assert_eq!(grid.sample_or_default([x as f64 + 0.5, 0.0]), 2.0);
+  assert_eq!(grid.try_sample([x as f64 + 0.5, 0.5]), Some(4.0));
+
+  // Positions beyond the range of chunk positions never hold a value
+  let beyond = i64::from(i32::MAX) * S as i64 + S as i64;
+  let chunks_count = grid.chunks_count();
+  assert_eq!(grid.try_sample([beyond as f64, 0.0]), None);
+  assert_eq!(grid.sample_or_default([beyond as f64, 0.0]), 0.0);
+  assert_eq!(grid.sample_insert_default([beyond as f64, 0.0]), 0.0);
+  assert_eq!(grid.chunks_count(), chunks_count);
+
+  let mut grid = ExGridSparse::<f32, S>::new();
+  grid.insert([-1, -1], 1.0);
+  grid.insert([0, -1], 2.0);
+  assert_eq!(grid.try_sample([-0.5, -1.0]), Some(1.5));
+  assert_eq!(grid.try_sample([-0.5, -0.5]), None);
+}
+
+#[test]
+fn test_sample_filters() {
+  perform_test_g!(test_sample_filters_g, 16, 8);
+}
+
+fn test_sample_filters_g<const S: usize>() {
+  let plane = |[x, y]: GlobalPos| 2.0 * x as f64 - 3.0 * y as f64;
+  let mut grid = ExGrid::<f64, S>::new();
+  let mut grid_sparse = ExGridSparse::<f64, S>::new();
+  for y in -8..8 {
+    for x in -8..8 {
+      *grid.get_mut_default([x, y]) = plane([x, y]);
+      grid_sparse.insert([x, y], plane([x, y]));
+    };
+  };
+
+  let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+  for pos in [[0.25, -1.5], [-3.75, 2.125], [4.0, 4.5], [-6.0, -6.0]] {
+    let expected = 2.0 * pos[0] - 3.0 * pos[1];
+    assert!(close(grid.sample_or_default_with(pos, Bicubic), expected), "{pos:?}");
+    assert!(close(grid_sparse.try_sample_with(pos, Bicubic).unwrap(), expected), "{pos:?}");
+    assert!(close(grid.try_sample_with(pos, Bilinear).unwrap(), expected), "{pos:?}");
+
+    let nearest = pos.map(|v| (v + 0.5).floor() as i64);
+    assert_eq!(grid.try_sample_with(pos, Nearest), Some(plane(nearest)), "{pos:?}");
+  };
+
+  assert_eq!(grid_sparse.try_sample_with([7.5, 0.0], Bicubic), None);
+  assert_eq!(grid_sparse.try_sample_with([7.0, 0.0], Bicubic), Some(plane([7, 0])));
+
+  let chunk = Chunk::<f64, S>::init(|[x, y]| plane([x as i64, y as i64]));
+  assert!(close(chunk.sample_with([1.5, 2.25], Bicubic), plane([0, 0]) + 2.0 * 1.5 - 3.0 * 2.25));
+  assert_eq!(chunk.sample_with([0.4, 0.6], Nearest), plane([0, 1]));
+
+  // Bicubic sampling reads one cell beyond either side of the sampled cells, which is clamped to the edge of the chunk
+  let edge = (S - 1) as f64 - 0.5;
+  assert!(chunk.sample_with([0.5, edge], Bicubic).is_finite());
+  assert!(chunk.sample_with([edge, 0.5], Bicubic).is_finite());
+}
+
+#[test]
+fn test_sample_borders() {
+  perform_test_g!(test_sample_borders_g);
+}
+
+fn test_sample_borders_g<const S: usize>() {
+  let last = S as f64 - 1.0;
+  let chunk = Chunk::<f64, S>::init(|[x, y]| (x + y * S) as f64);
+  assert_eq!(chunk.sample([last + 0.5, 0.0]), last);
+  assert!(chunk.sample_with([last + 0.5, last + 0.5], Bicubic).is_finite());
+  assert_eq!(chunk.sample_with_border([last + 0.5, 0.0], Bilinear, Border::Wrap), last / 2.0);
+  assert_eq!(chunk.sample_with_border([-1.0, 0.0], Nearest, Border::Wrap), last);
+  assert_eq!(chunk.sample_with_border([-1.0, 0.0], Nearest, Border::Mirror), 0.0);
+  assert_eq!(chunk.sample_with_border([S as f64, 0.0], Nearest, Border::Mirror), last);
+  assert_eq!(chunk.sample_with_border([0.0, -2.0], Nearest, Border::Constant(-1.0)), -1.0);
+  assert_eq!(chunk.sample_with_border([last + 0.5, 0.0], Bilinear, Border::Constant(0.0)), last / 2.0);
+  assert_eq!(chunk.sample_with_border([1.0e9, -1.0e9], Bicubic, Border::Clamp), chunk[[S - 1, 0]]);
+
+  // Cells around positions at the limits of `i64` are read without overflowing
+  for pos in [[f64::INFINITY, 0.5], [0.5, f64::NEG_INFINITY], [f64::MAX, f64::MIN]] {
+    for border in [Border::Clamp, Border::Wrap, Border::Mirror, Border::Constant(0.0)] {
+      let _ = chunk.sample_with_border(pos, Bicubic, border);
+    };
+  };
+}
+
+#[test]
+fn test_lerp_impls() {
+  #[derive(Debug, Clone, Copy, PartialEq)]
+  struct Color { r: u8, g: u8, b: u8 }
+
+  exgrid::impl_lerp!(Color { r, g, b });
+
+  assert_eq!(u8::lerp(0, 255, 0.5f32), 128);
+  assert_eq!(i32::lerp(-10, 10, 0.25f64), -5);
+  assert_eq!(u64::lerp(7, 7, 0.3f32), 7);
+  assert_eq!(u8::lerp(10, 0, 2.0f32), 0);
+
+  // Integers wider than the mantissa of a float are interpolated exactly
+  for x in [u64::MAX, u64::MAX - 1, (1 << 53) + 1] {
+    assert_eq!(u64::lerp(x, x, 0.3f64), x);
+  };
+
+  for x in [i128::MIN, i128::MAX, -(1 << 100) - 1] {
+    assert_eq!(i128::lerp(x, x, 0.7f32), x);
+  };
+
+  assert_eq!(u128::lerp(u128::MAX, u128::MAX, 0.5f64), u128::MAX);
+  assert_eq!(u64::lerp(0, u64::MAX, 0.5f64), 1 << 63);
+  assert_eq!(u64::lerp(u64::MAX - 4, u64::MAX, 0.5f64), u64::MAX - 2);
+  assert_eq!(i64::lerp((1 << 60) + 1, (1 << 60) + 3, 0.5f64), (1 << 60) + 2);
+  assert_eq!(i64::lerp(i64::MIN, i64::MAX, 0.0f64), i64::MIN);
+  assert_eq!(i128::lerp(i128::MIN, i128::MAX, 1.0f64), i128::MAX);
+  assert_eq!(i128::lerp(i128::MAX, i128::MIN, 0.5f64), -1);
+  assert_eq!(u128::lerp(0, u128::MAX, 0.25f64), u128::MAX / 4 + 1);
+  assert_eq!(<[f32; 3]>::lerp([0.0, 1.0, 2.0], [2.0, 3.0, 4.0], 0.5), [1.0, 2.0, 3.0]);
+  assert_eq!(<(u8, f64)>::lerp((0, 0.0), (10, 1.0), 0.5f64), (5, 0.5));
+  assert_eq!(Option::<f32>::lerp(Some(0.0), Some(2.0), 0.5), Some(1.0));
+  assert_eq!(Option::<f32>::lerp(Some(0.0), None, 0.5), None);
+
+  let black = Color { r: 0, g: 0, b: 0 };
+  let white = Color { r: 255, g: 255, b: 255 };
+  assert_eq!(Color::lerp(black, white, 0.5f64), Color { r: 128, g: 128, b: 128 });
+
+  let mut grid = ExGrid::<[u8; 3], 8>::new();
+  *grid.get_mut_default([0, 0]) = [0, 0, 0];
+  *grid.get_mut_default([1, 0]) = [100, 200, 50];
+  assert_eq!(grid.try_sample([0.5f32, 0.0]), Some([50, 100, 25]));
+}
+
+#[test]
+fn test_cells_in_rect() {
+  perform_test_g!(test_cells_in_rect_g);
+}
+
+fn test_cells_in_rect_g<const S: usize>() {
+  let mut rng = rand::thread_rng();
+  let mut grid = ExGrid::<u32, S>::new();
+  let mut grid_sparse = ExGridSparse::<u32, S>::new();
+  for (pos, value) in random_elements_near(&mut rng) {
+    *grid.get_mut_default(pos) = value;
+    grid_sparse.insert(pos, value);
+  };
+
+  let min = [rng.gen_range(-48..=0), rng.gen_range(-48..=0)];
+  let max = [rng.gen_range(0..=48), rng.gen_range(0..=48)];
+  let positions = (min[1]..=max[1])
+    .flat_map(|y| (min[0]..=max[0]).map(move |x| [x, y]))
+    .collect::<Vec<GlobalPos>>();
+
+  let window = grid.window_in_rect(min, max).collect::<Vec<_>>();
+  let expected = positions.iter().map(|&pos| (pos, grid.get(pos))).collect::<Vec<_>>();
+  assert_eq!(window, expected);
+
+  let cells = grid.cells_in_rect(min, max).collect::<Vec<_>>();
+  let expected = expected.into_iter().filter_map(|(pos, v)| Some((pos, v?))).collect::<Vec<_>>();
+  assert_eq!(cells, expected);
+
+  let window = grid_sparse.window_in_rect(min, max).collect::<Vec<_>>();
+  let expected = positions.iter().map(|&pos| (pos, grid_sparse.get(pos))).collect::<Vec<_>>();
+  assert_eq!(window, expected);
+
+  let cells = grid_sparse.cells_in_rect(min, max).collect::<Vec<_>>();
+  let expected = expected.into_iter().filter_map(|(pos, v)| Some((pos, v?))).collect::<Vec<_>>();
+  assert_eq!(cells, expected);
+
+  assert_eq!(grid.cells_in_rect(min, max).len(), grid.cells_in_rect(min, max).count());
+  assert_eq!(grid.cells_in_rect(max, min).count(), 0);
+
+  // Only existing chunks are visited, so rectangles covering the entire grid are cheap
+  let mut all = grid_sparse.cells().map(|(pos, &v)| (pos, v)).collect::<Vec<_>>();
+  all.sort_unstable_by_key(|&([x, y], _)| (y, x));
+  let cells = grid_sparse.cells_in_rect([i64::MIN; 2], [i64::MAX; 2]).map(|(pos, &v)| (pos, v)).collect::<Vec<_>>();
+  assert_eq!(cells, all);
+  let cells = grid_sparse.cells_in_rect_mut([i64::MIN; 2], [i64::MAX; 2]).map(|(pos, &mut v)| (pos, v)).collect::<Vec<_>>();
+  assert_eq!(cells, all);
+
+  for (_, value) in grid.cells_in_rect_mut(min, max) {
+    *value = value.wrapping_add(1);
+  };
+
+  for (pos, value) in grid_sparse.window_in_rect_mut(min, max) {
+    if let Some(value) = value {
+      *value = value.wrapping_add(1);
+    } else {
+      assert_eq!(grid.get(pos).copied().unwrap_or(1), 1, "{pos:?}");
+    };
+  };
+
+  for (pos, value) in grid_sparse.cells() {
+    assert_eq!(grid.get(pos), Some(value), "{pos:?}");
+  };
+
+  // Windows beyond the range of chunk positions yield nothing but missing cells
+  let edge = i64::from(i32::MAX) * S as i64 + S as i64 - 1;
+  let mut grid = ExGrid::<u32, S>::from_iter([([0, 0], 7), ([edge, 0], 8)]);
+  let mut grid_sparse = ExGridSparse::<u32, S>::from_iter([([0, 0], 7), ([edge, 0], 8)]);
+  let far = [1 << 40, 0];
+  let expected = [(far, None)];
+  assert_eq!(grid.window_in_rect(far, far).collect::<Vec<_>>(), expected);
+  assert_eq!(grid_sparse.window_in_rect(far, far).collect::<Vec<_>>(), expected);
+  assert!(grid.window_in_rect_mut(far, far).all(|(_, value)| value.is_none()));
+  assert!(grid_sparse.window_in_rect_mut(far, far).all(|(_, value)| value.is_none()));
+
+  let (min, max) = ([edge - 1, 0], [edge + 2, 0]);
+  let expected = [([edge - 1, 0], None), ([edge, 0], Some(&8)), ([edge + 1, 0], None), ([edge + 2, 0], None)];
+  assert_eq!(grid_sparse.window_in_rect(min, max).collect::<Vec<_>>(), expected);
+  assert_eq!(grid.window_in_rect(min, max).map(|(pos, v)| (pos, v.filter(|&&v| v != 0))).collect::<Vec<_>>(), expected);
+  for (pos, value) in grid_sparse.window_in_rect_mut(min, max) {
+    assert_eq!(value.is_some(), pos == [edge, 0], "{pos:?}");
+  };
+  for (pos, value) in grid.window_in_rect_mut(min, max) {
+    assert_eq!(value.is_some(), (edge - S as i64 + 1..=edge).contains(&pos[0]), "{pos:?}");
+  };
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_life_like_rule() {
+  use exgrid::automata::{LifeLikeRule, RuleParseError};
+
+  let conway = "B3/S23".parse::<LifeLikeRule>().unwrap();
+  assert_eq!(conway, LifeLikeRule::CONWAY);
+  assert_eq!("s23/b3".parse::<LifeLikeRule>(), Ok(conway));
+  assert_eq!("B3/S2/S3".parse::<LifeLikeRule>(), Err(RuleParseError::DuplicateSection('S')));
+  assert_eq!("B3ceaiknjqry/S23".parse::<LifeLikeRule>(), Ok(conway));
+  assert_eq!("B36/S9".parse::<LifeLikeRule>(), Err(RuleParseError::InvalidCount(9)));
+  assert_eq!("B1a/S".parse::<LifeLikeRule>(), Err(RuleParseError::InvalidLetter(1, 'a')));
+  assert_eq!("B3".parse::<LifeLikeRule>(), Err(RuleParseError::MissingSection('S')));
+  assert!(conway.is_totalistic());
+
+  let rule = "B2-a3/S12ce5y".parse::<LifeLikeRule>().unwrap();
+  assert!(!rule.is_totalistic());
+  assert_eq!(rule.to_string(), "B2-a3/S12ce5y");
+  assert_eq!(rule.to_string().parse::<LifeLikeRule>(), Ok(rule));
+  assert!(!rule.births(0b0000_0011) && rule.births(0b0000_0101));
+  assert!(rule.survives(0b1010_0000) && !rule.survives(0b0001_1000));
+
+  // One arrangement of four neighbours for each letter, each rotated from the arrangement drawn in the reference table
+  let letters = [
+    ('c', 0xa5), ('e', 0x5a), ('a', 0x96), ('i', 0xc6), ('k', 0x55), ('n', 0x95), ('j', 0x53),
+    ('q', 0xd1), ('r', 0x56), ('y', 0xc5), ('t', 0x47), ('w', 0x93), ('z', 0xc3)
+  ];
+  for (letter, neighbourhood) in letters {
+    for (other, _) in letters {
+      let rule = format!("B4{other}/S").parse::<LifeLikeRule>().unwrap();
+      assert_eq!(rule.births(neighbourhood), letter == other, "4{letter} born under B4{other}");
+    };
+  };
+
+  perform_test_g!(test_life_like_rule_g, 16, 8, 5, 3);
+}
+
+#[cfg(feature = "automata")]
+fn test_life_like_rule_g<const S: usize>() {
+  use exgrid::automata::{AutomataAdapter, LifeLikeRule};
+
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]].map(|[x, y]: GlobalPos| [x - 3, y - 3]);
+  let mut grid_sparse = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  let mut grid = glider.iter().map(|&pos| (pos, true)).collect::<ExGrid<bool, S>>();
+  let mut rule = LifeLikeRule::CONWAY;
+  for _ in 0..4 * S {
+    grid_sparse.step(&mut rule);
+    grid.step(&mut rule);
+  };
+
+  let offset = S as i64;
+  let mut expected = glider.map(|[x, y]| [x + offset, y + offset]).to_vec();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&grid_sparse), expected);
+  assert_eq!(sorted_live_cells(&grid), expected);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_multi_state_rules() {
+  use exgrid::automata::{GenerationsRule, LifeLikeRule, RuleParseError, Wireworld};
+
+  let brians_brain = "B2/S/C3".parse::<GenerationsRule>().unwrap();
+  assert_eq!(brians_brain, GenerationsRule::BRIANS_BRAIN);
+  assert_eq!("c3/s/b2".parse::<GenerationsRule>(), Ok(brians_brain));
+  assert_eq!(brians_brain.to_string(), "B2/S/C3");
+  assert_eq!("B2/S/C3/C4".parse::<GenerationsRule>(), Err(RuleParseError::DuplicateSection('C')));
+  assert_eq!("B2/S".parse::<GenerationsRule>(), Err(RuleParseError::MissingSection('C')));
+  assert_eq!("B2/S/C1".parse::<GenerationsRule>(), Err(RuleParseError::InvalidStates(1)));
+  assert_eq!("B2/S/C3x".parse::<GenerationsRule>(), Err(RuleParseError::UnexpectedChar('x')));
+  let rule = "B3/S2-a3/C5".parse::<GenerationsRule>().unwrap();
+  assert_eq!(rule.life(), "B3/S2-a3".parse::<LifeLikeRule>().unwrap());
+  assert_eq!(rule.states(), 5);
+  assert_eq!((rule.next_state(1, 0), rule.next_state(2, 0xff), rule.next_state(4, 0)), (2, 3, 0));
+  assert_eq!((rule.next_state(0, 0b0000_0111), rule.next_state(1, 0b0000_0101), rule.next_state(1, 0b0000_0011)), (1, 1, 2));
+
+  assert_eq!("wireworld".parse::<Wireworld>(), Ok(Wireworld));
+  assert_eq!("Wireworld".parse::<Wireworld>().unwrap().to_string(), "Wireworld");
+  assert_eq!("B3/S23".parse::<Wireworld>(), Err(RuleParseError::UnknownRule));
+
+  perform_test_g!(test_multi_state_rules_g, 16, 4);
+}
+
+#[cfg(feature = "automata")]
+fn test_multi_state_rules_g<const S: usize>() {
+  use exgrid::automata::{AutomataAdapter, GenerationsRule, Wireworld};
+
+  // A horizontal pair of live cells in Brian's Brain becomes a pair of spaceships travelling north and south
+  let mut rule = GenerationsRule::BRIANS_BRAIN;
+  let seed = [[0, 0], [1, 0]];
+  let mut grid_sparse = seed.iter().map(|&pos| (pos, 1)).collect::<ExGridSparse<u8, S>>();
+  let mut grid = seed.iter().map(|&pos| (pos, 1)).collect::<ExGrid<u8, S>>();
+  for _ in 0..2 * S {
+    grid_sparse.step(&mut rule);
+    grid.step(&mut rule);
+  };
+
+  let cells = |grid: &ExGrid<u8, S>| {
+    let mut cells = grid.cells().filter(|&(_, &state)| state != 0).map(|(pos, &state)| (pos, state)).collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells
+  };
+  let mut cells_sparse = grid_sparse.cells().map(|(pos, &state)| (pos, state)).collect::<Vec<_>>();
+  cells_sparse.sort_unstable();
+  assert_eq!(cells_sparse, cells(&grid));
+  assert!(grid_sparse.cells().all(|(_, &state)| state == 1 || state == 2));
+  assert!(grid_sparse.cells().any(|([_, y], _)| y > S as i64));
+  assert!(grid_sparse.cells().any(|([_, y], _)| y < -(S as i64)));
+
+  // An electron travels along a wire by one cell each generation, and the grid never expands
+  let mut rule = Wireworld;
+  let mut grid = (0..24).map(|x| ([x, 0], Wireworld::CONDUCTOR)).collect::<ExGrid<u8, S>>();
+  *grid.get_mut_default([0, 0]) = Wireworld::TAIL;
+  *grid.get_mut_default([1, 0]) = Wireworld::HEAD;
+  let chunks_count = grid.chunks_count();
+  for _ in 0..13 {
+    grid.step(&mut rule);
+  };
+
+  let mut expected = (0..24).map(|x| ([x, 0], Wireworld::CONDUCTOR)).collect::<Vec<_>>();
+  expected[13].1 = Wireworld::TAIL;
+  expected[14].1 = Wireworld::HEAD;
+  assert_eq!(cells(&grid), expected);
+  assert_eq!(grid.chunks_count(), chunks_count);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_expansion_helpers() {
+  use exgrid::ChunkSparse;
+  use exgrid::automata::{Expansion4, Expansion8};
+
+  let chunk = Chunk::<u8, 4>::init(|[x, y]| match [x, y] { [3, 0] => 2, [0, 2] => 1, _ => 0 });
+  let expansion4 = Expansion4 { n: true, s: false, e: true, w: true };
+  assert_eq!(chunk.edges_expansion(|&v| v != 0), expansion4);
+  assert_eq!(chunk.edges_expansion(|&v| v > 1), Expansion4 { n: true, e: true, ..Default::default() });
+  assert_eq!(chunk.edges_corners_expansion(|&v| v != 0), Expansion8 {
+    nn: true, ne: true, ee: true, ww: true, ..Default::default()
+  });
+
+  let chunk_sparse = ChunkSparse::from(chunk.map(|v| (v != 0).then_some(v)));
+  assert_eq!(chunk_sparse.edges_expansion(|_| true), expansion4);
+  assert_eq!(chunk_sparse.edges_not_empty_expansion(), expansion4);
+  assert_eq!(chunk_sparse.edges_corners_expansion(|&v| v == 1), Expansion8 { ww: true, ..Default::default() });
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_expansion_radius() {
+  use exgrid::ChunkSparse;
+  use exgrid::automata::{chunk_radius, Expansion, ExpansionRadius};
+
+  assert_eq!(chunk_radius::<8>(10), 2);
+  assert_eq!(chunk_radius::<8>(8), 1);
+  assert_eq!(ExpansionRadius::for_cell_radius::<8>(0), ExpansionRadius { r: 0 });
+
+  let mut offsets = Vec::new();
+  ExpansionRadius { r: 2 }.apply([10i32, -10], |pos| offsets.push(pos));
+  assert_eq!(offsets.len(), 24);
+  assert!(!offsets.contains(&[10, -10]) && offsets.contains(&[12, -8]));
+
+  let chunk = Chunk::<u8, 8>::init(|pos| (pos == [0, 0]) as u8);
+  let mut offsets = chunk.radius_expansion(10, |&v| v != 0).offsets;
+  let mut expected = (-2..=1).flat_map(|y| (-2..=1).map(move |x| [x, y])).filter(|&p| p != [0, 0]).collect::<Vec<_>>();
+  offsets.sort_unstable();
+  expected.sort_unstable();
+  assert_eq!(offsets, expected);
+  assert!(chunk.radius_expansion(10, |&v| v > 1).offsets.is_empty());
+
+  let chunk_sparse = ChunkSparse::from(chunk.map(|v| (v != 0).then_some(v)));
+  assert_eq!(chunk_sparse.radius_expansion(1, |_| true).offsets.len(), 3);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_contraction() {
+  perform_test_g!(test_contraction_g, 8, 4);
+}
+
+#[cfg(feature = "automata")]
+fn test_contraction_g<const S: usize>() {
+  use exgrid::automata::{Automata, AutomataAdapter, Expansion8, GridAutomataAdapter, LifeLikeRule, Topology};
+
+  let mut rule = LifeLikeRule::CONWAY;
+  let mut grid_sparse = ExGridSparse::<(), S>::from_iter([([0, 0], ())]);
+  let mut grid = ExGrid::<bool, S>::from_iter([([0, 0], true)]);
+  grid_sparse.step(&mut rule);
+  grid.step(&mut rule);
+  assert_eq!(grid_sparse.chunks_count(), 0);
+  assert_eq!(grid.chunks_count(), 0);
+
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+  let mut grid_sparse = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  let mut grid = glider.iter().map(|&pos| (pos, true)).collect::<ExGrid<bool, S>>();
+  for _ in 0..8 * S {
+    grid_sparse.step(&mut rule);
+    grid.step(&mut rule);
+    assert!(grid_sparse.chunks_count() <= 16);
+    assert!(grid.chunks_count() <= 16);
+  };
+
+  assert_eq!(grid_sparse.iter().count(), glider.len());
+
+  // Chunks next to a live chunk across the seam of a torus are kept
+  let torus = Topology::torus([0, 0], [3, 3]);
+  let block = [[0, 1], [1, 1], [0, 2], [1, 2]];
+  let mut grid_sparse = block.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  for _ in 0..2 {
+    grid_sparse.step_in(&mut rule, &torus);
+    assert!(grid_sparse.get_chunk([3, 0]).is_some());
+  };
+
+  // Automata which do not opt in to contraction never drop chunks
+  struct Persistent(LifeLikeRule);
+
+  impl<const S: usize> Automata<ExGrid<bool, S>> for Persistent {
+    type Expansion = Expansion8;
+
+    fn expansion(&mut self, chunk: &Chunk<bool, S>) -> Expansion8 {
+      Automata::<ExGrid<bool, S>>::expansion(&mut self.0, chunk)
+    }
+
+    fn simulate(&mut self, pos: GlobalPos, grid: &ExGrid<bool, S>) -> bool {
+      self.0.simulate(pos, grid)
+    }
+  }
+
+  let mut grid = ExGrid::<bool, S>::from_iter([([0, 0], true)]);
+  grid.step(&mut Persistent(rule));
+  assert!(grid.chunks_count() > 0);
+  assert_eq!(grid.iter().filter(|&&v| v).count(), 0);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_simulate_chunk() {
+  perform_test_g!(test_simulate_chunk_g, 16, 7, 3);
+}
+
+#[cfg(feature = "automata")]
+fn test_simulate_chunk_g<const S: usize>() {
+  use exgrid::automata::{Automata, AutomataAdapter, Expansion8, GridAutomataAdapter, LifeLikeRule, Topology};
+
+  /// Only simulates single cells, so that stepping uses the default `simulate_chunk`.
+  struct PerCell(LifeLikeRule);
+
+  impl<const S: usize> Automata<ExGridSparse<(), S>> for PerCell {
+    type Expansion = Expansion8;
+
+    fn expansion(&mut self, chunk: &exgrid::ChunkSparse<(), S>) -> Expansion8 {
+      Automata::<ExGridSparse<(), S>>::expansion(&mut self.0, chunk)
+    }
+
+    fn contraction(&mut self, chunk: &exgrid::ChunkSparse<(), S>) -> bool {
+      Automata::<ExGridSparse<(), S>>::contraction(&mut self.0, chunk)
+    }
+
+    fn simulate(&mut self, pos: GlobalPos, grid: &ExGridSparse<(), S>) -> Option<()> {
+      self.0.simulate(pos, grid)
+    }
+  }
+
+  let (mut grid, _) = soup_grids::<S>(&random_soup(192));
+  let mut grid_per_cell = grid.clone();
+  let mut rule = "B3/S2-i34q".parse::<LifeLikeRule>().unwrap();
+  let mut rule_per_cell = PerCell(rule);
+  for _ in 0..8 {
+    grid.step(&mut rule);
+    grid_per_cell.step(&mut rule_per_cell);
+    assert_eq!(grid, grid_per_cell);
+  };
+
+  // Cells read through the grid wrap around the edges of a torus just as the neighbourhood does
+  let torus = Topology::torus([-1, -1], [0, 0]);
+  for _ in 0..8 {
+    grid.step_in(&mut rule, &torus);
+    grid_per_cell.step_in(&mut rule_per_cell, &torus);
+    assert_eq!(grid, grid_per_cell);
+  };
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_topology() {
+  use exgrid::automata::Topology;
+
+  let torus = Topology::<Chunk<bool, 4>>::torus([-1, -1], [1, 0]);
+  assert_eq!(torus.resolve([2, 1]), Some([-1, -1]));
+  assert_eq!(torus.resolve([-2, -2]), Some([1, 0]));
+  let bounded = Topology::<Chunk<bool, 4>>::bounded([0, 0], [1, 1], true);
+  assert_eq!(bounded.resolve([1, 2]), None);
+  assert_eq!(bounded.resolve([1, 1]), Some([1, 1]));
+  assert_eq!(bounded.boundary().map(|chunk| chunk[[3, 3]]), Some(true));
+  let extreme = Topology::<Chunk<bool, 4>>::torus([i32::MIN, 0], [i32::MAX, 0]);
+  assert_eq!(extreme.resolve([i32::MAX, 1]), Some([i32::MAX, 0]));
+
+  perform_test_g!(test_topology_g, 8, 3);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+#[should_panic]
+fn test_topology_inverted_torus() {
+  exgrid::automata::Topology::<Chunk<bool, 4>>::torus([1, 0], [0, 0]);
+}
+
+#[cfg(feature = "automata")]
+fn test_topology_g<const S: usize>() {
+  use exgrid::automata::{Automata, Expansion8, GridAutomataAdapter, LifeLikeRule, Topology};
+
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+  let mut rule = LifeLikeRule::CONWAY;
+
+  // A glider on a torus returns to where it started after crossing the whole world
+  let torus = Topology::torus([0, 0], [1, 1]);
+  let mut grid_sparse = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  let mut grid = glider.iter().map(|&pos| (pos, true)).collect::<ExGrid<bool, S>>();
+  for _ in 0..8 * S {
+    grid_sparse.step_in(&mut rule, &torus);
+    grid.step_in(&mut rule, &Topology::torus([0, 0], [1, 1]));
+    assert!(grid_sparse.chunks().all(|(&[x, y], _)| (0..2).contains(&x) && (0..2).contains(&y)));
+  };
+
+  let mut expected = glider.to_vec();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&grid_sparse), expected);
+  assert_eq!(sorted_live_cells(&grid), expected);
+
+  // A glider in a bounded world with a vacant boundary collides with the edge and stays within it
+  let bounded = Topology::<exgrid::ChunkSparse<(), S>>::bounded([0, 0], [1, 1], None);
+  let mut grid_sparse = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  for _ in 0..8 * S {
+    grid_sparse.step_in(&mut rule, &bounded);
+    assert!(grid_sparse.chunks().all(|(&[x, y], _)| (0..2).contains(&x) && (0..2).contains(&y)));
+  };
+
+  let size = 2 * S as i64;
+  assert!(grid_sparse.cells().all(|([x, y], _)| (0..size).contains(&x) && (0..size).contains(&y)));
+
+  /// Moves every cell one cell east, only reading cells through the grid.
+  struct ShiftEast;
+
+  impl<const S: usize> Automata<ExGrid<bool, S>> for ShiftEast {
+    type Expansion = Expansion8;
+
+    fn expansion(&mut self, _: &Chunk<bool, S>) -> Expansion8 {
+      Expansion8::default()
+    }
+
+    fn simulate(&mut self, [x, y]: GlobalPos, grid: &ExGrid<bool, S>) -> bool {
+      grid.get([x - 1, y]).copied().unwrap_or(false)
+    }
+  }
+
+  // Cells read across an edge wrap around on a torus, and read the boundary in a bounded world
+  let mut grid = ExGrid::<bool, S>::from_iter([([S as i64 - 1, 0], true)]);
+  grid.step_in(&mut ShiftEast, &Topology::torus([0, 0], [0, 0]));
+  assert_eq!(sorted_live_cells(&grid), [[0, 0]]);
+  assert_eq!(grid.chunks_count(), 1);
+
+  grid.step_in(&mut ShiftEast, &Topology::<Chunk<bool, S>>::bounded([0, 0], [0, 0], true));
+  let mut expected = (0..S as i64).map(|y| [0, y]).chain((S > 1).then_some([1, 0])).collect::<Vec<_>>();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&grid), expected);
+  assert_eq!(grid.chunks_count(), 1);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_step_n() {
+  perform_test_g!(test_step_n_g, 16, 5, 2);
+}
+
+#[cfg(feature = "automata")]
+fn test_step_n_g<const S: usize>() {
+  use exgrid::automata::{GenerationStats, GridAutomataAdapter, LifeLikeRule, Topology};
+
+  let mut rule = LifeLikeRule::CONWAY;
+  let blinker = [[-1, 0], [0, 0], [1, 0]];
+  let mut grid = blinker.iter().map(|&pos| (pos, true)).collect::<ExGrid<bool, S>>();
+  let report = grid.step_n(&mut rule, 6);
+  assert_eq!(report.generations.len(), 6);
+  assert!(report.populations().all(|population| population == 3));
+  assert!(report.generations.iter().all(|stats| stats.changed == 4));
+  assert_eq!(report.total_changed(), 24);
+
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+  let mut grid_sparse = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  let mut grid_sparse_observed = grid_sparse.clone();
+  let chunks_count = grid_sparse.chunks_count();
+  let report = grid_sparse.step_n(&mut rule, 4 * S);
+  assert_eq!(report.final_population(), Some(glider.len()));
+  assert_eq!(grid_sparse.chunks_count() + report.total_chunks_dropped(), chunks_count + report.total_chunks_created());
+
+  let mut generations = Vec::new();
+  let mut scratch = ExGridSparse::default();
+  grid_sparse_observed.step_n_with(&mut scratch, &mut rule, &Topology::Infinite, 4 * S, |grid, stats| {
+    assert_eq!(grid.iter().count(), stats.population);
+    generations.push(stats);
+  });
+  assert_eq!(generations, report.generations);
+  assert_eq!(grid_sparse_observed, grid_sparse);
+  assert_eq!(ExGridSparse::<(), S>::default().step_n(&mut rule, 1).generations, [GenerationStats::default()]);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_period_detection() {
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+  let grid = glider.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), 4>>();
+  let grid_moved = glider.iter().map(|&[x, y]| ([x - 37, y + 5], ())).collect::<ExGridSparse<(), 16>>();
+  assert_eq!(grid.occupied_bounds(), Some(([0, 0], [2, 2])));
+  assert_eq!(grid_moved.occupied_bounds(), Some(([-37, 5], [-35, 7])));
+  assert_eq!(grid.canonical_hash(), grid_moved.canonical_hash());
+  assert_ne!(grid.canonical_hash(), ExGridSparse::<(), 4>::default().canonical_hash());
+
+  perform_test_g!(test_period_detection_g, 16, 5);
+}
+
+#[cfg(feature = "automata")]
+fn test_period_detection_g<const S: usize>() {
+  use exgrid::automata::{LifeLikeRule, PatternHistory, Repetition};
+
+  let mut rule = LifeLikeRule::CONWAY;
+  let mut run = |cells: &[GlobalPos]| {
+    let mut grid = cells.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+    PatternHistory::new().run(&mut grid, &mut rule, 16)
+  };
+
+  let block = run(&[[0, 0], [1, 0], [0, 1], [1, 1]]).unwrap();
+  assert_eq!(block, Repetition { first: 0, period: 1, displacement: [0, 0] });
+  assert!(block.is_still() && block.is_oscillator());
+
+  let blinker = run(&[[-1, 0], [0, 0], [1, 0]]).unwrap();
+  assert_eq!(blinker, Repetition { first: 0, period: 2, displacement: [0, 0] });
+  assert!(!blinker.is_still() && blinker.is_oscillator());
+
+  let glider = run(&[[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]]).unwrap();
+  assert_eq!(glider, Repetition { first: 0, period: 4, displacement: [1, 1] });
+  assert!(glider.is_spaceship());
+
+  // A pre-block becomes a block after one generation
+  let pre_block = run(&[[0, 0], [1, 0], [0, 1]]).unwrap();
+  assert_eq!(pre_block, Repetition { first: 1, period: 1, displacement: [0, 0] });
+
+  // The R-pentomino takes far longer than 16 generations to stabilise
+  assert_eq!(run(&[[1, 0], [2, 0], [0, 1], [1, 1], [1, 2]]), None);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_hashlife() {
+  use exgrid::automata::{Hashlife, LifeLikeRule};
+
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+  let mut hashlife = Hashlife::from_cells(LifeLikeRule::CONWAY, glider);
+  assert_eq!(hashlife.population(), 5);
+  assert!(hashlife.get([2, 1]) && !hashlife.get([1, 1]) && !hashlife.get([1000, -1000]));
+  hashlife.step_pow2(10);
+  hashlife.collect_garbage();
+  hashlife.step(3 * 1024);
+  assert_eq!(hashlife.generation(), 4096);
+  let mut expected = glider.map(|[x, y]| [x + 1024, y + 1024]).to_vec();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&hashlife), expected);
+
+  hashlife.set([1025, 1024], false);
+  hashlife.set([-5000, 20], true);
+  assert_eq!(hashlife.population(), 5);
+  assert!(hashlife.get([-5000, 20]));
+
+  // Cells may be set anywhere within the range of positions
+  let corners = [[i64::MIN, i64::MIN], [i64::MAX, 0], [0, i64::MAX]];
+  let mut hashlife = Hashlife::from_cells(LifeLikeRule::CONWAY, corners);
+  assert!(corners.iter().all(|&pos| hashlife.get(pos)) && !hashlife.get([i64::MAX, 1]));
+  let mut expected = corners.to_vec();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&hashlife), expected);
+  hashlife.step(1);
+  assert_eq!(hashlife.population(), 0);
+
+  // A glider far from the origin moves just as it does near it
+  let far = 1 << 61;
+  let mut hashlife = Hashlife::from_cells(LifeLikeRule::CONWAY, glider.map(|[x, y]| [x + far, y - far]));
+  hashlife.step(64);
+  let mut expected = glider.map(|[x, y]| [x + far + 16, y - far + 16]).to_vec();
+  expected.sort_unstable();
+  assert_eq!(sorted_live_cells(&hashlife), expected);
+
+  // Oscillators can be stepped by every generation a `u64` can count
+  let blinker = [[0, 1], [1, 1], [2, 1]];
+  let mut hashlife = Hashlife::from_cells(LifeLikeRule::CONWAY, blinker);
+  hashlife.step_pow2(63);
+  assert_eq!(sorted_live_cells(&hashlife), blinker);
+  hashlife.step(u64::MAX >> 1);
+  assert_eq!(hashlife.generation(), u64::MAX);
+  assert_eq!(sorted_live_cells(&hashlife), [[1, 0], [1, 1], [1, 2]]);
+
+  perform_test_g!(test_hashlife_g, 8);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+#[should_panic]
+fn test_hashlife_pow2_limit() {
+  exgrid::automata::Hashlife::default().step_pow2(64);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+#[should_panic]
+fn test_hashlife_generation_limit() {
+  let mut hashlife = exgrid::automata::Hashlife::default();
+  hashlife.step(u64::MAX);
+  hashlife.step(1);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+#[should_panic]
+fn test_hashlife_position_limit() {
+  use exgrid::automata::{Hashlife, LifeLikeRule};
+
+  // A glider moving south-east past the last position
+  let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]].map(|[x, y]| [i64::MAX - 2 + x, y]);
+  Hashlife::from_cells(LifeLikeRule::CONWAY, glider).step(4);
+}
+
+#[cfg(feature = "automata")]
+fn test_hashlife_g<const S: usize>() {
+  use exgrid::automata::{AutomataAdapter, Hashlife, LifeLikeRule};
+
+  let soup = random_soup(192);
+  for rule in ["B3/S23", "B36/S23", "B2-a3/S12ce5y"] {
+    let mut rule = rule.parse::<LifeLikeRule>().unwrap();
+    let (mut grid_sparse, mut grid) = soup_grids::<S>(&soup);
+    let mut hashlife = Hashlife::from_grid_sparse(rule, &grid_sparse);
+    let mut hashlife_dense = Hashlife::from_grid(rule, &grid);
+    for _ in 0..21 {
+      grid_sparse.step(&mut rule);
+      grid.step(&mut rule);
+    };
+
+    hashlife.step(21);
+    hashlife_dense.step_pow2(4);
+    hashlife_dense.step(5);
+    let expected = sorted_live_cells(&grid_sparse);
+    assert_eq!(sorted_live_cells(&hashlife.to_grid_sparse::<S, std::hash::RandomState>()), expected);
+    assert_eq!(sorted_live_cells(&hashlife_dense), expected);
+    assert_eq!(sorted_live_cells(&hashlife.to_grid::<S, std::hash::RandomState>()), expected);
+    assert_eq!(sorted_live_cells(&grid), expected);
+  };
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_step_active() {
+  perform_test_g!(test_step_active_g, 16, 7);
+}
+
+#[cfg(feature = "automata")]
+fn test_step_active_g<const S: usize>() {
+  use exgrid::automata::{chunk_radius, ActiveChunks, Automata, AutomataAdapter, ExpansionRadius, GridAutomataAdapter, LifeLikeRule};
+
+  let mut rule = LifeLikeRule::CONWAY;
+  let block = [[0, 0], [1, 0], [0, 1], [1, 1]].map(|[x, y]: GlobalPos| [x + 64, y + 64]);
+  let mut grid = block.iter().map(|&pos| (pos, ())).collect::<ExGridSparse<(), S>>();
+  let mut active = ActiveChunks::new();
+  assert_eq!(active.changed_count(), None);
+  grid.step_active(&mut rule, &mut active);
+  assert_eq!(active.changed_count(), Some(0));
+
+  // Cells placed without marking their chunk as changed are not simulated
+  grid.insert([64 + 2, 64], ());
+  let mut expected = grid.clone();
+  expected.step(&mut rule);
+  grid.step_active(&mut rule, &mut active);
+  assert_eq!(grid.iter().count(), 5);
+  active.mark_cell_changed::<S>([64 + 2, 64]);
+  grid.step_active(&mut rule, &mut active);
+  assert_eq!(sorted_live_cells(&grid), sorted_live_cells(&expected));
+  assert!(active.is_changed(exgrid::grid::decompose::<S>([64 + 1, 64]).0));
+
+  let mut soup = random_soup(192);
+  soup.extend(block);
+  let (mut grid_sparse, mut grid) = soup_grids::<S>(&soup);
+  let mut grid_sparse_active = grid_sparse.clone();
+  let mut grid_active = grid.clone();
+  let (mut active_sparse, mut active) = (ActiveChunks::new(), ActiveChunks::new());
+  for _ in 0..16 {
+    grid_sparse.step(&mut rule);
+    grid.step(&mut rule);
+    grid_sparse_active.step_active(&mut rule, &mut active_sparse);
+    grid_active.step_active(&mut rule, &mut active);
+    assert_eq!(grid_sparse_active, grid_sparse);
+    assert_eq!(grid_active, grid);
+  };
+
+  /// Cells are born further than a chunk to the right of a live cell, and never die.
+  struct Spread;
+
+  impl<const S: usize> Automata<ExGridSparse<(), S>> for Spread {
+    type Expansion = ExpansionRadius;
+
+    fn expansion(&mut self, _: &exgrid::ChunkSparse<(), S>) -> ExpansionRadius {
+      ExpansionRadius::for_cell_radius::<S>(2 * S + 1)
+    }
+
+    fn reach(&self) -> u32 {
+      chunk_radius::<S>(2 * S + 1)
+    }
+
+    fn simulate(&mut self, [x, y]: GlobalPos, grid: &ExGridSparse<(), S>) -> Option<()> {
+      grid.get([x, y]).or(grid.get([x - 2 * S as i64 - 1, y])).copied()
+    }
+  }
+
+  let mut grid = ExGridSparse::<(), S>::from_iter([([0, 0], ())]);
+  let mut grid_active = grid.clone();
+  let mut active = ActiveChunks::new();
+  for _ in 0..4 {
+    grid.step(&mut Spread);
+    grid_active.step_active(&mut Spread, &mut active);
+    assert_eq!(grid_active, grid);
+  };
+
+  assert_eq!(grid_active.iter().count(), 5);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_block_automata() {
+  perform_test_g!(test_block_automata_g, 8, 4, 3);
+}
+
+#[cfg(feature = "automata")]
+fn test_block_automata_g<const S: usize>() {
+  use exgrid::automata::{BlockAutomataAdapter, BlockRule};
+
+  /// Cells fall into the vacant cell below them within each block.
+  struct Fall;
+
+  impl BlockRule<bool> for Fall {
+    fn step_block(&mut self, [nw, ne, sw, se]: [bool; 4], _: bool) -> [bool; 4] {
+      let [nw, sw] = if nw && !sw { [false, true] } else { [nw, sw] };
+      let [ne, se] = if ne && !se { [false, true] } else { [ne, se] };
+      [nw, ne, sw, se]
+    }
+  }
+
+  impl BlockRule<Option<()>> for Fall {
+    fn step_block(&mut self, block: [Option<()>; 4], odd: bool) -> [Option<()>; 4] {
+      BlockRule::<bool>::step_block(self, block.map(|v| v.is_some()), odd).map(|v| v.then_some(()))
+    }
+  }
+
+  let mut grid_sparse = ExGridSparse::<(), S>::from_iter([([0, 0], ())]);
+  let mut grid = ExGrid::<bool, S>::from_iter([([0, 0], true)]);
+  grid_sparse.step_blocks_n(&mut Fall, false, 10);
+  grid.step_blocks_n(&mut Fall, false, 10);
+  assert_eq!(grid_sparse.cells().map(|(pos, _)| pos).collect::<Vec<_>>(), [[0, 10]]);
+  assert_eq!(grid.cells().filter(|&(_, &v)| v).map(|(pos, _)| pos).collect::<Vec<_>>(), [[0, 10]]);
+  assert_eq!((grid_sparse.chunks_count(), grid.chunks_count()), (1, 10 / S + 1));
+
+  let (mut grid_sparse, mut grid) = soup_grids::<S>(&random_soup(128));
+  let population = grid_sparse.iter().count();
+  for i in 0..16 {
+    grid_sparse.step_blocks(&mut Fall, i % 2 == 1);
+    grid.step_blocks(&mut Fall, i % 2 == 1);
+  };
+
+  let cells_sparse = sorted_live_cells(&grid_sparse);
+  assert_eq!(cells_sparse.len(), population);
+  assert_eq!(cells_sparse, sorted_live_cells(&grid));
+
+  // Dense grids keep chunks which are left vacant
+  let mut grid = ExGrid::<bool, S>::from_iter([([0, 0], true), ([0, S as i64], false)]);
+  grid.step_blocks(&mut Fall, false);
+  assert_eq!(grid.chunks_count(), 2);
+  grid.step_blocks_n(&mut Fall, true, 2 * S - 2);
+  assert_eq!(grid.chunks_count(), 2);
+  assert_eq!(grid.cells().filter(|&(_, &v)| v).count(), 1);
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_step_async() {
+  perform_test_g!(test_step_async_g, 8, 5);
+}
+
+#[cfg(feature = "automata")]
+fn test_step_async_g<const S: usize>() {
+  use exgrid::automata::{AsyncAutomataAdapter, Automata, AutomataAdapter, Expansion4, LifeLikeRule, UpdateOrder};
+
+  /// Cells become live when their western neighbour is live, and never die.
+  struct Spread;
+
+  impl<const S: usize> Automata<ExGridSparse<(), S>> for Spread {
+    type Expansion = Expansion4;
+
+    fn expansion(&mut self, _: &exgrid::ChunkSparse<(), S>) -> Expansion4 {
+      Expansion4::default()
+    }
+
+    fn simulate(&mut self, [x, y]: GlobalPos, grid: &ExGridSparse<(), S>) -> Option<()> {
+      (grid.get([x, y]).is_some() || grid.get([x - 1, y]).is_some()).then_some(())
+    }
+  }
+
+  fn run<const S: usize>(order: Option<UpdateOrder>) -> Vec<GlobalPos> {
+    let mut grid = ExGridSparse::<(), S>::from_iter([([1, 0], ())]);
+    match order {
+      Some(mut order) => grid.step_async(&mut Spread, &mut order),
+      None => grid.step(&mut Spread)
+    };
+
+    sorted_live_cells(&grid)
+  }
+
+  let row = |end: i64| (1..end).map(|x| [x, 0]).collect::<Vec<GlobalPos>>();
+  assert_eq!(run::<S>(None), row(3));
+  assert_eq!(run::<S>(Some(UpdateOrder::Checkerboard)), row(4));
+  assert_eq!(run::<S>(Some(UpdateOrder::RowSweep)), row(S as i64));
+  let cells = run::<S>(Some(UpdateOrder::random(3)));
+  assert_eq!(run::<S>(Some(UpdateOrder::random(3))), cells);
+  assert!(cells.len() < S && cells == row(cells.len() as i64 + 1));
+
+  let (mut grid_sparse, mut grid) = soup_grids::<S>(&random_soup(192));
+  let [mut order_sparse, mut order] = [UpdateOrder::random(5); 2];
+  let mut rule = LifeLikeRule::CONWAY;
+  grid_sparse.step_async_n(&mut rule, &mut order_sparse, 8);
+  grid.step_async_n(&mut rule, &mut order, 8);
+  assert_eq!(order, order_sparse);
+  assert!(matches!(order, UpdateOrder::RandomSequential(rng) if rng.generation() == 8));
+  assert_eq!(sorted_live_cells(&grid_sparse), sorted_live_cells(&grid));
+}
+
+#[cfg(feature = "automata")]
+#[test]
+fn test_cell_rng() {
+  use exgrid::automata::{Automata, CellRng, Expansion8, LifeLikeRule};
+
+  let rng = CellRng::new(7);
+  assert_eq!(rng.u64([3, -4]), CellRng::new(7).u64([3, -4]));
+  assert_ne!(rng.u64([3, -4]), rng.u64([-4, 3]));
+  assert_ne!(rng.u64([3, -4]), CellRng::new(8).u64([3, -4]));
+  let mut next = rng;
+  next.advance();
+  assert_ne!(rng.u64([3, -4]), next.u64([3, -4]));
+  let values = rng.stream([0, 0]).take(4).collect::<Vec<_>>();
+  assert_eq!(values[0], rng.u64([0, 0]));
+  assert!(values.windows(2).all(|pair| pair[0] != pair[1]));
+  assert!((0..256).all(|x| (0.0..1.0).contains(&rng.f64([x, 0]))));
+
+  /// Conway's Game of Life, in which live cells randomly die a tenth of the time.
+  #[derive(Clone)]
+  struct NoisyLife(CellRng);
+
+  impl<const S: usize> Automata<ExGridSparse<(), S>> for NoisyLife {
+    type Expansion = Expansion8;
+
+    fn expansion(&mut self, chunk: &exgrid::ChunkSparse<(), S>) -> Expansion8 {
+      let mut rule = LifeLikeRule::CONWAY;
+      Automata::<ExGridSparse<(), S>>::expansion(&mut rule, chunk)
+    }
+
+    fn simulate(&mut self, pos: GlobalPos, grid: &ExGridSparse<(), S>) -> Option<()> {
+      let mut rule = LifeLikeRule::CONWAY;
+      let alive = rule.simulate(pos, grid).is_some();
+      (alive && !self.0.chance(pos, 0.1)).then_some(())
+    }
+
+    fn finish_step(&mut self) {
+      self.0.advance();
+    }
+  }
+
+  fn run<const S: usize>(soup: &[GlobalPos], seed: u64, parallel: bool) -> Vec<GlobalPos> {
+    use exgrid::automata::AutomataAdapter;
+
+    let (mut grid, _) = soup_grids::<S>(soup);
+    let mut rule = NoisyLife(CellRng::new(seed));
+    for _ in 0..8 {
+      #[cfg(feature = "multi-thread")]
+      if parallel {
+        exgrid::automata::ParAutomataAdapter::par_step(&mut grid, &mut rule);
+        continue;
+      };
+
+      let _ = parallel;
+      grid.step(&mut rule);
+    };
+
+    assert_eq!(rule.0.generation(), 8);
+    sorted_live_cells(&grid)
+  }
+
+  let soup = random_soup(192);
+  let cells = run::<16>(&soup, 1, false);
+  assert_eq!(run::<16>(&soup, 1, false), cells);
+  assert_eq!(run::<5>(&soup, 1, false), cells);
+  assert_eq!(run::<5>(&soup, 1, true), cells);
+}
+
+#[cfg(all(feature = "automata", feature = "multi-thread"))]
+#[test]
+fn test_par_step() {
+  perform_test_g!(test_par_step_g, 16, 5);
+}
+
+#[cfg(all(feature = "automata", feature = "multi-thread"))]
+fn test_par_step_g<const S: usize>() {
+  use exgrid::automata::{AutomataAdapter, LifeLikeRule, ParAutomataAdapter};
+
+  let (mut grid_sparse, mut grid) = soup_grids::<S>(&random_soup(192));
+  let mut grid_sparse_par = grid_sparse.clone();
+  let mut grid_par = grid.clone();
+  let mut rule = "B36/S23".parse::<LifeLikeRule>().unwrap();
+  for _ in 0..8 {
+    grid_sparse.step(&mut rule);
+    grid_sparse_par.par_step(&mut rule);
+    grid.step(&mut rule);
+    grid_par.par_step(&mut rule);
+    assert_eq!(grid_sparse, grid_sparse_par);
+    assert_eq!(grid, grid_par);
+  };
 }
 
 #[cfg(feature = "serde")]
@@ -114,6 +1352,13 @@ fn random_positions() -> impl Iterator<Item = GlobalPos> {
   }).take(count)
 }
 
+fn random_elements_near(rng: &mut impl Rng) -> Vec<(GlobalPos, u32)> {
+  let count = rng.gen_range(64..128);
+  std::iter::repeat_with(|| {
+    ([rng.gen_range(-64..=64), rng.gen_range(-64..=64)], rng.gen::<u32>())
+  }).take(count).collect()
+}
+
 fn random_elements() -> impl Iterator<Item = (GlobalPos, u32)> {
   let mut rng = rand::thread_rng();
   let count = rng.gen_range(16..32);
@@ -121,3 +1366,51 @@ fn random_elements() -> impl Iterator<Item = (GlobalPos, u32)> {
     (random_position(&mut rng), rng.gen::<u32>())
   }).take(count)
 }
+
+#[cfg(feature = "automata")]
+fn random_soup(count: usize) -> Vec<GlobalPos> {
+  let mut rng = rand::thread_rng();
+  std::iter::repeat_with(|| [rng.gen_range(-12..=12), rng.gen_range(-12..=12)]).take(count).collect()
+}
+
+/// Builds a sparse and a dense grid of live cells at the given positions.
+#[cfg(feature = "automata")]
+fn soup_grids<const S: usize>(soup: &[GlobalPos]) -> (ExGridSparse<(), S>, ExGrid<bool, S>) {
+  let grid_sparse = soup.iter().map(|&pos| (pos, ())).collect();
+  let grid = soup.iter().map(|&pos| (pos, true)).collect();
+  (grid_sparse, grid)
+}
+
+/// Collects the positions of every live cell in row-major order, for comparing the results of two-state automata.
+#[cfg(feature = "automata")]
+fn sorted_live_cells(grid: &impl LiveCells) -> Vec<GlobalPos> {
+  let mut cells = grid.live_cells();
+  cells.sort_unstable();
+  cells
+}
+
+#[cfg(feature = "automata")]
+trait LiveCells {
+  fn live_cells(&self) -> Vec<GlobalPos>;
+}
+
+#[cfg(feature = "automata")]
+impl<const S: usize, H: std::hash::BuildHasher> LiveCells for ExGridSparse<(), S, H> {
+  fn live_cells(&self) -> Vec<GlobalPos> {
+    self.cells().map(|(pos, _)| pos).collect()
+  }
+}
+
+#[cfg(feature = "automata")]
+impl<const S: usize, H: std::hash::BuildHasher> LiveCells for ExGrid<bool, S, H> {
+  fn live_cells(&self) -> Vec<GlobalPos> {
+    self.cells().filter(|&(_, &alive)| alive).map(|(pos, _)| pos).collect()
+  }
+}
+
+#[cfg(feature = "automata")]
+impl LiveCells for exgrid::automata::Hashlife {
+  fn live_cells(&self) -> Vec<GlobalPos> {
+    self.cells()
+  }
+}